@@ -1,45 +1,74 @@
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 
 use crossterm::cursor::{Hide, MoveTo, Show};
-use crossterm::event::{self, Event};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, MouseEventKind};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::ExecutableCommand;
+use futures::StreamExt;
 use log::debug;
+use tokio::time;
 
+use crate::checklist;
+use crate::clipboard;
 use crate::edit::clamp_cursor;
 use crate::external_edit::edit_in_external_editor;
-use crate::io::{load_lines, save_lines};
+use regex::Regex;
+
+use crate::fuzzy::{fuzzy_match, regex_match, smart_case_match};
+use crate::increment::bump_at_cursor;
+use crate::format::format_for_path;
+use crate::io::{load_lines, save_lines_checked, SaveOutcome};
 use crate::keys::{map_key, Key};
 use crate::model::{
-    App, EditIntent, EditTarget, LineItem, Mode, Task, UndoState, MAX_UNDO_HISTORY,
+    App, CheckState, EditIntent, EditTarget, LineItem, Mode, PendingOperator, Revision, SearchMode,
+    SearchState, Task,
 };
 use crate::text_input::TextInput;
+use crate::watch::FileWatcher;
 
 const FILE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 const DEFAULT_WINDOW_WIDTH: u16 = 80;
 
 impl App {
     pub fn new(path: PathBuf) -> Result<Self, String> {
-        let (lines, mod_time) = load_lines(&path).map_err(|e| e.to_string())?;
+        let format = format_for_path(&path);
+        let (lines, mod_time) = load_lines(&path, &*format).map_err(|e| e.to_string())?;
         let template = default_task_template(&lines);
+        let base_lines = lines.clone();
+        let root_revision = Revision {
+            lines: lines.clone(),
+            cursor: 0,
+            timestamp: mod_time,
+            parent: None,
+            children: Vec::new(),
+        };
 
         Ok(Self {
             file_path: path,
+            format,
             lines,
+            base_lines,
             cursor: 0,
             mode: Mode::Normal,
             text_input: TextInput::new(),
             search_input: TextInput::new(),
+            search_state: SearchState::default(),
+            search_origin: 0,
+            search_mode: SearchMode::Fuzzy,
+            compiled_regex: None,
             input_placeholder: "Describe the task".to_string(),
             edit_intent: EditIntent::None,
             edit_target: EditTarget::Task,
             edit_index: None,
             insert_index: None,
             edit_template: template,
+            completion: None,
             status_message: String::new(),
             error: None,
             last_modified: mod_time,
@@ -50,56 +79,105 @@ impl App {
             window_height: 0,
             renderer_width: 0,
             external_edit_idx: None,
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-            pending_d: false,
+            revisions: vec![root_revision],
+            current_revision: 0,
+            pending_revision: false,
+            pending_operator: PendingOperator::None,
+            pending_g: false,
+            pending_operator_g: false,
+            register: Vec::new(),
+            count_prefix: String::new(),
             scroll_offset: 0,
+            row_to_line: Vec::new(),
+            body_row_offset: 0,
             should_quit: false,
         })
     }
 
-    pub fn run(mut self) -> Result<(), String> {
-        let _terminal = TerminalGuard::new().map_err(|e| e.to_string())?;
+    // Runs the event loop on the current tokio runtime. Input no longer
+    // blocks a dedicated poll call: `EventStream`, the watcher's event
+    // channel, and the stat-polling fallback tick are all just branches of
+    // one `tokio::select!`, so a later background task (autosave, due-date
+    // reminders, ...) can join the same `select!` without fighting a
+    // blocking read for the terminal.
+    pub async fn run(mut self, mouse: bool) -> Result<(), String> {
+        let mut terminal = TerminalGuard::with_mouse(mouse).map_err(|e| e.to_string())?;
         self.refresh_window_size();
         self.ensure_renderer_width(self.window_width);
         self.render_to_terminal()?;
 
+        // Prefer an inotify/FSEvents watcher so external edits reload almost
+        // instantly; if the platform/filesystem doesn't support it, fall
+        // back to the old stat-every-second polling below.
+        let mut watcher = FileWatcher::new(&self.file_path).ok();
+
+        let mut events = EventStream::new();
+        let mut ticker = time::interval(Duration::from_millis(250));
         let mut last_file_check = Instant::now();
-        let mut dirty = false;
 
-        // Main loop: poll for input, check file changes, and re-render on updates.
+        // Main loop: await input, check file changes, and re-render on updates.
         while !self.should_quit {
-            let now = Instant::now();
-            if now.duration_since(last_file_check) >= FILE_CHECK_INTERVAL {
-                self.handle_file_check();
-                last_file_check = now;
-                dirty = true;
-            }
-
-            let timeout = FILE_CHECK_INTERVAL
-                .saturating_sub(now.duration_since(last_file_check))
-                .min(Duration::from_millis(250));
-
-            if event::poll(timeout).map_err(|e| e.to_string())? {
-                match event::read().map_err(|e| e.to_string())? {
-                    Event::Key(key_event) => {
-                        let key = map_key(key_event);
-                        self.handle_key(key);
+            let mut dirty = false;
+
+            tokio::select! {
+                maybe_event = events.next() => {
+                    match maybe_event {
+                        Some(Ok(Event::Key(key_event))) => {
+                            let key = map_key(key_event);
+                            self.handle_key(key, &mut terminal);
+                            dirty = true;
+                        }
+                        Some(Ok(Event::Resize(w, h))) => {
+                            self.window_width = w;
+                            self.window_height = h;
+                            self.ensure_renderer_width(w);
+                            dirty = true;
+                        }
+                        Some(Ok(Event::Mouse(mouse_event))) => {
+                            self.handle_mouse(mouse_event);
+                            dirty = true;
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(err)) => return Err(err.to_string()),
+                        // The input stream only ends if stdin was closed out
+                        // from under us; there's nothing left to wait on.
+                        None => self.should_quit = true,
+                    }
+                }
+                // Only a real branch of this `select!` (rather than drained
+                // inside the ticker tick below) actually gets reloads
+                // "near-instant": the loop blocks here until the watcher
+                // thread forwards an event, instead of waiting for the next
+                // 250ms tick to notice it was already sitting in the channel.
+                // `select!` still evaluates a disabled branch's expression, so
+                // the no-watcher case must not panic here; it is never polled.
+                changed = async {
+                    match watcher.as_mut() {
+                        Some(w) => w.next_change().await,
+                        None => std::future::pending().await,
+                    }
+                }, if watcher.is_some() =>
+                {
+                    if changed {
+                        self.handle_file_check();
                         dirty = true;
                     }
-                    Event::Resize(w, h) => {
-                        self.window_width = w;
-                        self.window_height = h;
-                        self.ensure_renderer_width(w);
+                }
+                // Stat-polling fallback for platforms/filesystems `notify`
+                // doesn't support there; only armed when there's no watcher.
+                _ = ticker.tick(), if watcher.is_none() => {
+                    let now = Instant::now();
+                    let due = now.duration_since(last_file_check) >= FILE_CHECK_INTERVAL;
+                    if due {
+                        last_file_check = now;
+                        self.handle_file_check();
                         dirty = true;
                     }
-                    _ => {}
                 }
             }
 
             if dirty {
                 self.render_to_terminal()?;
-                dirty = false;
             }
         }
 
@@ -127,41 +205,99 @@ impl App {
         }
     }
 
-    fn handle_key(&mut self, key: Key) {
+    fn handle_key(&mut self, key: Key, terminal: &mut TerminalGuard) {
         debug!("Key: {:?}", key);
         match self.mode {
             Mode::Edit => self.handle_edit_key(key),
-            Mode::Normal => self.handle_normal_key(key),
+            Mode::Normal => self.handle_normal_key(key, terminal),
             Mode::Search => self.handle_search_key(key),
         }
     }
 
-    fn handle_normal_key(&mut self, key: Key) {
-        if self.pending_d {
-            self.pending_d = false;
-            if key == Key::Char('d') {
-                self.delete_current_line();
+    // Only click-to-select and wheel scroll; editing/typing still happens
+    // through the keyboard, so this only touches `cursor` and never fires
+    // while a key-driven editor is open.
+    fn handle_mouse(&mut self, event: crossterm::event::MouseEvent) {
+        if self.mode == Mode::Edit {
+            return;
+        }
+        match event.kind {
+            MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
+                let row = event.row as usize;
+                if row < self.body_row_offset {
+                    return;
+                }
+                if let Some(Some(idx)) = self.row_to_line.get(row - self.body_row_offset) {
+                    self.cursor = *idx;
+                    self.clear_selection();
+                }
+            }
+            MouseEventKind::ScrollUp => self.move_cursor_visible(-1),
+            MouseEventKind::ScrollDown => self.move_cursor_visible(1),
+            _ => {}
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: Key, terminal: &mut TerminalGuard) {
+        if self.pending_operator != PendingOperator::None {
+            // Digits between the operator and its motion extend the count
+            // (the "2" in `d2j`) instead of canceling the operator.
+            if let Key::Char(c @ '0'..='9') = key {
+                if !self.pending_operator_g && (c != '0' || !self.count_prefix.is_empty()) {
+                    self.count_prefix.push(c);
+                    self.status_message = format!("count {}", self.count_prefix);
+                    return;
+                }
+            }
+            if self.run_pending_operator(key) {
+                self.count_prefix.clear();
                 return;
             }
+            // Any other key cancels the operator and falls through to be
+            // handled normally below (e.g. `d` then `/` still starts a search).
+            self.pending_operator = PendingOperator::None;
+        }
+
+        if self.pending_g {
+            self.pending_g = false;
+            match key {
+                Key::Char('-') => self.earlier(Duration::from_secs(60)),
+                Key::Char('+') => self.later(Duration::from_secs(60)),
+                Key::Char('g') => self.move_cursor_to_visible_first(),
+                _ => {}
+            }
+            self.count_prefix.clear();
+            return;
         }
 
         if key == Key::Char('/') {
-            self.pending_d = false;
             self.clear_selection();
+            if !self.search_active() {
+                self.search_origin = self.cursor;
+            }
             self.mode = Mode::Search;
             if self.search_active() {
                 self.search_input.insert_char('/');
             } else {
                 self.search_input.reset();
             }
-            self.ensure_cursor_visible_for_search();
+            self.preview_search_from_origin();
             return;
         }
 
         if key == Key::Esc {
+            // Clears any pending state: a stuck count prefix would otherwise
+            // silently carry into the next operator/motion (e.g. typing `3`
+            // then aborting with Esc, then `dd`, would delete 3 lines).
+            self.count_prefix.clear();
+            self.pending_operator = PendingOperator::None;
+            self.pending_g = false;
+            self.pending_operator_g = false;
+
             let mut cleared = false;
             if self.search_active() {
                 self.search_input.reset();
+                self.search_state = SearchState::default();
                 cleared = true;
             }
             if self.selection_active {
@@ -174,16 +310,52 @@ impl App {
             return;
         }
 
+        if let Key::Char(c @ '1'..='9') = key {
+            self.count_prefix.push(c);
+            self.status_message = format!("count {}", self.count_prefix);
+            return;
+        }
+        if let Key::Char('0') = key {
+            if !self.count_prefix.is_empty() {
+                self.count_prefix.push('0');
+                self.status_message = format!("count {}", self.count_prefix);
+                return;
+            }
+        }
+
         match key {
             Key::Ctrl('c') => self.should_quit = true,
             Key::Char('q') => self.should_quit = true,
+            Key::Ctrl('a') => self.bump_under_cursor(1),
+            Key::Ctrl('x') => self.bump_under_cursor(-1),
+            Key::Char('y') => {
+                if self.selection_active {
+                    self.yank();
+                } else {
+                    self.pending_operator = PendingOperator::Yank;
+                    self.status_message = "y-".to_string();
+                    // Keep `count_prefix` alive for the motion that follows.
+                    return;
+                }
+            }
+            Key::Char('p') => self.paste(false),
+            Key::Char('P') => self.paste(true),
+            Key::Char('n') => self.search_step(false),
+            Key::Char('N') => self.search_step(true),
             Key::Char('j') | Key::Down => self.move_cursor_visible(1),
             Key::Char('k') | Key::Up => self.move_cursor_visible(-1),
-            Key::Char('g') => self.move_cursor_to_visible_first(),
+            Key::Char('g') => {
+                self.pending_g = true;
+                self.status_message = "g-".to_string();
+                // Keep `count_prefix` alive in case `gg` is a motion count.
+                return;
+            }
             Key::Char('G') => self.move_cursor_to_visible_last(),
             Key::Char('d') => {
-                self.pending_d = true;
+                self.pending_operator = PendingOperator::Delete;
                 self.status_message = "d-".to_string();
+                // Keep `count_prefix` alive for the motion that follows.
+                return;
             }
             Key::Char('u') => {
                 self.undo();
@@ -216,15 +388,16 @@ impl App {
                 }
             }
             Key::Char('e') => {
-                let _ = self.start_external_edit();
+                let _ = self.start_external_edit(terminal);
             }
             Key::Char('i') => self.start_edit_current(),
             Key::Char('o') => self.start_insert_task_at(self.cursor + 1),
             Key::Char('O') => self.start_insert_task_at(self.cursor),
             Key::Char('S') => self.start_insert_section_at(self.cursor + 1),
-            Key::Char('r') => match load_lines(&self.file_path) {
+            Key::Char('r') => match load_lines(&self.file_path, &*self.format) {
                 Ok((lines, mod_time)) => {
                     self.lines = lines;
+                    self.base_lines = self.lines.clone();
                     self.cursor = clamp_cursor(self.cursor, self.lines.len());
                     self.normalize_selection();
                     self.last_modified = mod_time;
@@ -236,9 +409,130 @@ impl App {
             },
             _ => {}
         }
+        self.count_prefix.clear();
+    }
+
+    // Consume and reset the accumulated digit-key count prefix (defaults to 1).
+    fn take_count(&mut self) -> i64 {
+        let count = self.count_prefix.parse().unwrap_or(1);
+        self.count_prefix.clear();
+        count
+    }
+
+    // Completes the pending `d`/`y` operator once its motion key arrives,
+    // returning whether `key` was consumed as that motion (`j`/`k`/`G`/`gg`,
+    // or a repeat of the operator's own char for the linewise `dd`/`yy`
+    // form). `count` (digits typed before the operator and/or the motion)
+    // selects how many lines the operator spans.
+    fn run_pending_operator(&mut self, key: Key) -> bool {
+        let op = self.pending_operator;
+
+        if self.pending_operator_g {
+            self.pending_operator_g = false;
+            if key != Key::Char('g') {
+                return false;
+            }
+            return self.finish_pending_operator(op, 0);
+        }
+
+        let same_op_char = matches!(
+            (op, key),
+            (PendingOperator::Delete, Key::Char('d')) | (PendingOperator::Yank, Key::Char('y'))
+        );
+
+        let target = if same_op_char {
+            let count = self.take_count().max(1) as usize;
+            (self.cursor + count - 1).min(self.lines.len().saturating_sub(1))
+        } else {
+            match key {
+                Key::Char('j') | Key::Down => {
+                    let count = self.take_count().max(1) as usize;
+                    (self.cursor + count).min(self.lines.len().saturating_sub(1))
+                }
+                Key::Char('k') | Key::Up => {
+                    let count = self.take_count().max(1) as usize;
+                    self.cursor.saturating_sub(count)
+                }
+                Key::Char('G') => self.lines.len().saturating_sub(1),
+                Key::Char('g') => {
+                    // Matches the non-operator `gg` handling (`pending_g`):
+                    // a lone `g` waits for a second one rather than
+                    // completing the operator against line 0 immediately.
+                    self.pending_operator_g = true;
+                    return true;
+                }
+                _ => return false,
+            }
+        };
+
+        self.finish_pending_operator(op, target)
+    }
+
+    // Runs `op` over the range between the cursor and `target` (inclusive),
+    // or just the current line if they're equal, then clears the operator.
+    fn finish_pending_operator(&mut self, op: PendingOperator, target: usize) -> bool {
+        let (start, end) = if self.cursor <= target {
+            (self.cursor, target)
+        } else {
+            (target, self.cursor)
+        };
+
+        match op {
+            PendingOperator::Delete if start == end => self.delete_current_line(),
+            PendingOperator::Delete => self.delete_line_range(start, end),
+            PendingOperator::Yank if start == end => self.yank(),
+            PendingOperator::Yank => self.yank_line_range(start, end),
+            PendingOperator::None => {}
+        }
+        self.pending_operator = PendingOperator::None;
+        true
+    }
+
+    // Ctrl-A/Ctrl-X: bump a number or date/time token in the current line's
+    // text. Normal mode has no horizontal cursor (`self.cursor` is a line
+    // index, not a column), so this always passes `col: 0` — `bump_at_cursor`
+    // resolves that to the leftmost such token in the text, not "the one the
+    // cursor is on".
+    fn bump_under_cursor(&mut self, delta: i64) {
+        let count = self.take_count();
+        let Some(LineItem::Task(task)) = self.lines.get(self.cursor) else {
+            return;
+        };
+        match bump_at_cursor(&task.text, 0, delta, count) {
+            Some(new_text) => {
+                self.save_undo_state();
+                if let Some(LineItem::Task(task)) = self.lines.get_mut(self.cursor) {
+                    task.text = new_text;
+                }
+                self.save_and_set_status("Bumped value");
+            }
+            None => self.status_message = "Nothing to increment".to_string(),
+        }
     }
 
     fn handle_edit_key(&mut self, key: Key) {
+        if self.completion.is_some() {
+            match key {
+                Key::Up | Key::Ctrl('p') => {
+                    self.completion_select(-1);
+                    return;
+                }
+                Key::Down | Key::Ctrl('n') => {
+                    self.completion_select(1);
+                    return;
+                }
+                Key::Tab | Key::Enter => {
+                    self.accept_completion();
+                    return;
+                }
+                Key::Esc => {
+                    self.dismiss_completion();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
         match key {
             Key::Esc => {
                 let raw = self.text_input.value().to_string();
@@ -266,6 +560,7 @@ impl App {
                         self.cursor = idx;
                     }
                     self.text_input.reset();
+                    self.completion = None;
                     return;
                 }
 
@@ -281,47 +576,65 @@ impl App {
                     self.change_indent(-1);
                 }
             }
+            Key::Ctrl('w') => self.text_input.delete_word_backward(),
+            Key::Ctrl('k') => self.text_input.kill_to_end(),
+            Key::Ctrl('u') => self.text_input.kill_to_start(),
+            Key::Ctrl('d') => self.text_input.delete_word_forward(),
             Key::Char(c) => self.text_input.insert_char(c),
             Key::Backspace => self.text_input.backspace(),
             Key::Delete => self.text_input.delete(),
             Key::Left => self.text_input.move_left(),
             Key::Right => self.text_input.move_right(),
+            Key::WordLeft => self.text_input.move_word_left(),
+            Key::WordRight => self.text_input.move_word_right(),
             Key::Home => self.text_input.move_home(),
             Key::End => self.text_input.move_end(),
             _ => {}
         }
+
+        if self.edit_target == EditTarget::Task {
+            self.update_completion();
+        }
     }
 
     fn handle_search_key(&mut self, key: Key) {
         match key {
             Key::Esc => {
                 self.search_input.reset();
+                self.search_state = SearchState::default();
+                self.cursor = self.search_origin;
                 self.mode = Mode::Normal;
                 self.status_message = "Search cleared".to_string();
                 self.clear_selection();
             }
             Key::Enter => {
+                // Commit the jump: leave search mode with the cursor already
+                // resting on the previewed match.
                 self.mode = Mode::Normal;
-                self.toggle_tasks();
             }
             Key::Char(c) => {
                 self.search_input.insert_char(c);
-                self.ensure_cursor_visible_for_search();
+                self.preview_search_from_origin();
+                self.recompute_search_matches();
             }
             Key::Backspace => {
                 let remaining = self.search_input.value().chars().count();
                 if remaining <= 1 {
                     self.search_input.reset();
+                    self.search_state = SearchState::default();
+                    self.cursor = self.search_origin;
                     self.mode = Mode::Normal;
                     self.status_message = "Search cleared".to_string();
                     return;
                 }
                 self.search_input.backspace();
-                self.ensure_cursor_visible_for_search();
+                self.preview_search_from_origin();
+                self.recompute_search_matches();
             }
             Key::Delete => {
                 self.search_input.delete();
-                self.ensure_cursor_visible_for_search();
+                self.preview_search_from_origin();
+                self.recompute_search_matches();
             }
             Key::Left => self.search_input.move_left(),
             Key::Right => self.search_input.move_right(),
@@ -329,6 +642,11 @@ impl App {
             Key::End => self.search_input.move_end(),
             Key::Up => self.move_cursor_visible(-1),
             Key::Down => self.move_cursor_visible(1),
+            Key::Ctrl('t') => {
+                self.search_mode = self.search_mode.next();
+                self.recompute_search_matches();
+                self.preview_search_from_origin();
+            }
             _ => {}
         }
     }
@@ -354,17 +672,25 @@ impl App {
             if let Some((start, end)) = self.selection_range() {
                 for i in start..=end {
                     if let Some(LineItem::Task(task)) = self.lines.get_mut(i) {
-                        task.completed = !task.completed;
+                        task.state = task.state.toggled();
                         count += 1;
-                        last_toggled = Some(task.completed);
+                        last_toggled = Some(task.state.is_on());
                     }
                 }
+                for i in start..=end {
+                    checklist::recompute_ancestors(&mut self.lines, i);
+                }
             }
             self.selection_active = false;
-        } else if let Some(LineItem::Task(task)) = self.lines.get_mut(self.cursor) {
-            task.completed = !task.completed;
+        } else if matches!(self.lines.get(self.cursor), Some(LineItem::Task(_))) {
+            let new_state = match self.lines.get(self.cursor) {
+                Some(LineItem::Task(task)) => task.state.toggled(),
+                _ => unreachable!(),
+            };
+            checklist::set_state_cascade(&mut self.lines, self.cursor, new_state);
+            checklist::recompute_ancestors(&mut self.lines, self.cursor);
             count = 1;
-            last_toggled = Some(task.completed);
+            last_toggled = Some(new_state.is_on());
         }
 
         if count == 0 {
@@ -383,7 +709,7 @@ impl App {
     }
 
     // Run external editor synchronously while suspending the TUI.
-    fn start_external_edit(&mut self) -> Result<(), String> {
+    fn start_external_edit(&mut self, terminal: &mut TerminalGuard) -> Result<(), String> {
         if self.lines.is_empty() {
             return Ok(());
         }
@@ -395,7 +721,11 @@ impl App {
         self.clear_selection();
         self.external_edit_idx = Some(self.cursor);
 
-        match edit_in_external_editor(&task_text) {
+        terminal.cleanup();
+        let result = edit_in_external_editor(&task_text);
+        terminal.resume();
+
+        match result {
             Ok(Some(new_text)) => {
                 if let Some(idx) = self.external_edit_idx {
                     if let Some(LineItem::Task(task)) = self.lines.get_mut(idx) {
@@ -417,6 +747,103 @@ impl App {
         Ok(())
     }
 
+    // Yank the current task, or the whole visual range, into both the
+    // internal register and the OS clipboard.
+    fn yank(&mut self) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let items: Vec<LineItem> = if let Some((start, end)) = self.selection_range() {
+            self.lines[start..=end].to_vec()
+        } else {
+            vec![self.lines[self.cursor].clone()]
+        };
+        self.selection_active = false;
+
+        let text = clipboard::serialize(&items, &*self.format);
+        let count = items.len();
+        self.register = items;
+        let _ = clipboard::set_system_clipboard(&text);
+
+        self.status_message = if count == 1 {
+            "Yanked 1 line".to_string()
+        } else {
+            format!("Yanked {} lines", count)
+        };
+    }
+
+    fn paste(&mut self, above: bool) {
+        let default_indent = match self.lines.get(self.cursor) {
+            Some(LineItem::Task(task)) => task.indent.clone(),
+            _ => String::new(),
+        };
+
+        let items = match clipboard::get_system_clipboard() {
+            Ok(text) if !text.trim().is_empty() => {
+                clipboard::parse(&text, &default_indent, &*self.format)
+            }
+            _ => self.register.clone(),
+        };
+        if items.is_empty() {
+            self.status_message = "Nothing to paste".to_string();
+            return;
+        }
+
+        let idx = if self.lines.is_empty() {
+            0
+        } else if above {
+            self.cursor
+        } else {
+            self.cursor + 1
+        };
+        let idx = idx.min(self.lines.len());
+
+        self.save_undo_state();
+        self.clear_selection();
+        for (offset, item) in items.into_iter().enumerate() {
+            self.lines.insert(idx + offset, item);
+        }
+        self.cursor = idx;
+        self.save_and_set_status("Pasted");
+    }
+
+    // Deletes lines `[start, end]` (inclusive) as a single undo step. Used by
+    // operator-pending motions that span more than one line (`d2j`, `dG`,
+    // ...); the single-line `dd` case still goes through `delete_current_line`.
+    fn delete_line_range(&mut self, start: usize, end: usize) {
+        if self.lines.is_empty() {
+            self.status_message = "Nothing to delete".to_string();
+            return;
+        }
+        let end = end.min(self.lines.len() - 1);
+        self.save_undo_state();
+        self.clear_selection();
+        let count = end - start + 1;
+        self.lines.drain(start..=end);
+        self.cursor = clamp_cursor(start, self.lines.len());
+        self.save_and_set_status(&format!("Deleted {} line(s)", count));
+    }
+
+    // Yanks lines `[start, end]` (inclusive) into the register and OS
+    // clipboard, the multi-line counterpart of `yank`'s current-line/visual
+    // cases for operator-pending motions (`y2j`, `yG`, ...).
+    fn yank_line_range(&mut self, start: usize, end: usize) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let end = end.min(self.lines.len() - 1);
+        let items: Vec<LineItem> = self.lines[start..=end].to_vec();
+        let count = items.len();
+        let text = clipboard::serialize(&items, &*self.format);
+        self.register = items;
+        let _ = clipboard::set_system_clipboard(&text);
+        self.status_message = if count == 1 {
+            "Yanked 1 line".to_string()
+        } else {
+            format!("Yanked {} lines", count)
+        };
+    }
+
     fn delete_current_line(&mut self) {
         if self.lines.is_empty() {
             self.status_message = "Nothing to delete".to_string();
@@ -454,14 +881,43 @@ impl App {
     }
 
     fn save_and_set_status(&mut self, msg: &str) {
-        match save_lines(&self.file_path, &self.lines) {
-            Ok(mod_time) => {
+        match save_lines_checked(
+            &self.file_path,
+            &self.base_lines,
+            &self.lines,
+            self.last_modified,
+            &*self.format,
+        ) {
+            Ok(SaveOutcome::Saved(mod_time)) => {
                 self.last_modified = mod_time;
+                self.base_lines = self.lines.clone();
                 self.status_message = msg.to_string();
                 self.error = None;
             }
+            Ok(SaveOutcome::Merged {
+                mod_time,
+                lines,
+                conflicts,
+            }) => {
+                self.lines = lines;
+                self.cursor = clamp_cursor(self.cursor, self.lines.len());
+                self.normalize_selection();
+                self.last_modified = mod_time;
+                self.base_lines = self.lines.clone();
+                self.status_message = if conflicts.is_empty() {
+                    format!("{} (merged with changes made on disk)", msg)
+                } else {
+                    format!("{} (merged, {} conflict(s) kept disk's value)", msg, conflicts.len())
+                };
+                self.error = None;
+            }
             Err(err) => self.error = Some(err.to_string()),
         }
+
+        if self.pending_revision {
+            self.pending_revision = false;
+            self.commit_revision();
+        }
     }
 
     // Poll the file's modification time; reload unless currently editing.
@@ -491,9 +947,10 @@ impl App {
             return;
         }
 
-        match load_lines(&self.file_path) {
+        match load_lines(&self.file_path, &*self.format) {
             Ok((lines, mod_time)) => {
                 self.lines = lines;
+                self.base_lines = self.lines.clone();
                 self.cursor = clamp_cursor(self.cursor, self.lines.len());
                 self.normalize_selection();
                 self.last_modified = mod_time;
@@ -560,38 +1017,122 @@ impl App {
         if self.mode == Mode::Edit || !self.search_active() {
             return (0..self.lines.len()).collect();
         }
-        let query = self.search_query();
-        let mut indices = Vec::new();
-        let mut current_section: Option<usize> = None;
-        let mut section_included = false;
-        for (idx, line) in self.lines.iter().enumerate() {
-            match line {
-                LineItem::Section { .. } => {
-                    current_section = Some(idx);
-                    section_included = false;
-                }
-                LineItem::Task(task) => {
-                    if task.text.contains(query) {
-                        if let Some(section_idx) = current_section {
-                            if !section_included {
-                                indices.push(section_idx);
-                                section_included = true;
-                            }
-                        }
-                        indices.push(idx);
-                    }
+        let query = self.search_query().to_string();
+        let mut matches: Vec<(usize, i64)> = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                let text = match line {
+                    LineItem::Section { title, .. } => title.as_str(),
+                    LineItem::Task(task) => task.text.as_str(),
+                    LineItem::Raw { .. } | LineItem::Blank => "",
+                };
+                self.match_line(text, &query).map(|(score, _)| (idx, score))
+            })
+            .collect();
+        // Stable sort preserves document order for equal scores.
+        matches.sort_by(|a, b| b.1.cmp(&a.1));
+        matches.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    // Dispatches to the matcher for the active `search_mode`. In `Regex`
+    // mode this relies on `compiled_regex`, which only `recompute_search_matches`
+    // updates, so a bad pattern mid-edit keeps matching against the last
+    // valid one instead of matching nothing.
+    pub(crate) fn match_line(&self, text: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+        match self.search_mode {
+            SearchMode::Fuzzy => fuzzy_match(text, query),
+            SearchMode::SmartCase => smart_case_match(text, query),
+            SearchMode::Regex => self.compiled_regex.as_ref().and_then(|re| regex_match(re, text)),
+        }
+    }
+
+    // Recompute `search_state` from the current query, in document order (as
+    // opposed to `visible_indices`' relevance ordering) so `n`/`N` step
+    // through occurrences the way they appear in the file. Surfaces a
+    // "match i/N" count in `status_message`.
+    fn recompute_search_matches(&mut self) {
+        let query = self.search_query().to_string();
+        if query.is_empty() {
+            self.search_state = SearchState::default();
+            self.compiled_regex = None;
+            return;
+        }
+
+        if self.search_mode == SearchMode::Regex {
+            match Regex::new(&query) {
+                Ok(re) => self.compiled_regex = Some(re),
+                Err(err) => {
+                    // Keep the previous match set and compiled pattern;
+                    // just surface the error.
+                    self.error = Some(err.to_string());
+                    return;
                 }
             }
         }
-        indices
+
+        let mut matches = Vec::new();
+        let mut spans = Vec::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            let text = match line {
+                LineItem::Section { title, .. } => title.as_str(),
+                LineItem::Task(task) => task.text.as_str(),
+                LineItem::Raw { .. } | LineItem::Blank => "",
+            };
+            if let Some((_, positions)) = self.match_line(text, &query) {
+                matches.push(idx);
+                spans.push(match_span(text, &positions));
+            }
+        }
+
+        let current = matches.iter().position(|&i| i == self.cursor).unwrap_or(0);
+        let len = matches.len();
+        self.search_state = SearchState { query, matches, current, spans };
+
+        self.status_message = if len == 0 {
+            "No matches".to_string()
+        } else {
+            format!("match {}/{}", current + 1, len)
+        };
+    }
+
+    // Advance (or, with `backward`, retreat) to the next search match,
+    // wrapping around, and move the cursor there.
+    fn search_step(&mut self, backward: bool) {
+        if self.search_state.is_empty() {
+            self.status_message = "No matches".to_string();
+            return;
+        }
+        let len = self.search_state.len();
+        self.search_state.current = if backward {
+            (self.search_state.current + len - 1) % len
+        } else {
+            (self.search_state.current + 1) % len
+        };
+        self.cursor = self.search_state.matches[self.search_state.current];
+        self.clear_selection();
+        self.status_message = format!("match {}/{}", self.search_state.current + 1, len);
     }
 
-    pub(crate) fn ensure_cursor_visible_for_search(&mut self) {
+    // Live preview: jump the cursor to the first match at or after
+    // `search_origin` (wrapping around to the first match otherwise), so
+    // results scroll into view as the user types. `search_origin` itself is
+    // left untouched here and restored verbatim on cancel.
+    fn preview_search_from_origin(&mut self) {
         if self.mode == Mode::Edit || !self.search_active() {
             return;
         }
-        let indices = self.visible_indices();
-        self.ensure_cursor_visible_in(&indices);
+        let mut indices = self.visible_indices();
+        if indices.is_empty() {
+            return;
+        }
+        indices.sort_unstable();
+        self.cursor = indices
+            .iter()
+            .copied()
+            .find(|&i| i >= self.search_origin)
+            .unwrap_or(indices[0]);
     }
 
     pub(crate) fn ensure_cursor_visible_in(&mut self, indices: &[usize]) {
@@ -640,51 +1181,112 @@ impl App {
         }
     }
 
+    // Marks the upcoming mutation as undo-tracked. The revision itself is
+    // materialized later by `save_and_set_status`, once `self.lines` holds
+    // the post-edit content — a node cloned here, before the mutation,
+    // would just be a duplicate of its own parent.
     pub(crate) fn save_undo_state(&mut self) {
-        let state = UndoState {
-            lines: self.lines.clone(),
-            cursor: self.cursor,
-        };
-        self.undo_stack.push(state);
-        if self.undo_stack.len() > MAX_UNDO_HISTORY {
-            self.undo_stack.remove(0);
-        }
-        self.redo_stack.clear();
+        self.pending_revision = true;
     }
 
-    fn undo(&mut self) {
-        if self.undo_stack.is_empty() {
-            self.status_message = "Nothing to undo".to_string();
-            return;
-        }
-        let redo_state = UndoState {
+    // Appends a new revision as a child of `current_revision`, using the
+    // current (post-edit) `lines`/`cursor`, then moves `current_revision`
+    // to it. Children are only ever pushed, never reordered or removed, so
+    // `redo_step`'s `children.last()` deterministically means "most
+    // recently created branch".
+    fn commit_revision(&mut self) {
+        let revision = Revision {
             lines: self.lines.clone(),
             cursor: self.cursor,
+            timestamp: SystemTime::now(),
+            parent: Some(self.current_revision),
+            children: Vec::new(),
         };
-        self.redo_stack.push(redo_state);
+        let new_index = self.revisions.len();
+        self.revisions[self.current_revision].children.push(new_index);
+        self.revisions.push(revision);
+        self.current_revision = new_index;
+    }
 
-        if let Some(state) = self.undo_stack.pop() {
-            self.lines = state.lines;
-            self.cursor = clamp_cursor(state.cursor, self.lines.len());
+    // Undo one revision, returning the timestamp of the edit it reverted.
+    fn undo_step(&mut self) -> Option<SystemTime> {
+        let parent = self.revisions[self.current_revision].parent?;
+        let timestamp = self.revisions[self.current_revision].timestamp;
+        self.lines = self.revisions[parent].lines.clone();
+        self.cursor = clamp_cursor(self.revisions[parent].cursor, self.lines.len());
+        self.current_revision = parent;
+        Some(timestamp)
+    }
+
+    // Redo one revision, returning the timestamp of the edit it re-applied.
+    fn redo_step(&mut self) -> Option<SystemTime> {
+        let child = *self.revisions[self.current_revision].children.last()?;
+        let timestamp = self.revisions[child].timestamp;
+        self.lines = self.revisions[child].lines.clone();
+        self.cursor = clamp_cursor(self.revisions[child].cursor, self.lines.len());
+        self.current_revision = child;
+        Some(timestamp)
+    }
+
+    fn undo(&mut self) {
+        if self.undo_step().is_some() {
             self.status_message = "Undo".to_string();
+        } else {
+            self.status_message = "Nothing to undo".to_string();
         }
     }
 
     fn redo(&mut self) {
-        if self.redo_stack.is_empty() {
+        if self.redo_step().is_some() {
+            self.status_message = "Redo".to_string();
+        } else {
             self.status_message = "Nothing to redo".to_string();
-            return;
         }
-        let undo_state = UndoState {
-            lines: self.lines.clone(),
-            cursor: self.cursor,
-        };
-        self.undo_stack.push(undo_state);
+    }
 
-        if let Some(state) = self.redo_stack.pop() {
-            self.lines = state.lines;
-            self.cursor = clamp_cursor(state.cursor, self.lines.len());
-            self.status_message = "Redo".to_string();
+    // "Earlier": step to the parent while the revision being left happened
+    // within the last `window`, following Helix's
+    // History::earlier/UndoKind::Steps(time) but walking the tree instead
+    // of a stack.
+    fn earlier(&mut self, window: Duration) {
+        let cutoff = SystemTime::now().checked_sub(window).unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut count = 0;
+        while self.revisions[self.current_revision].timestamp >= cutoff
+            && self.revisions[self.current_revision].parent.is_some()
+        {
+            if self.undo_step().is_none() {
+                break;
+            }
+            count += 1;
+        }
+        if count == 0 {
+            self.status_message = "Nothing to undo in that window".to_string();
+        } else {
+            self.save_and_set_status(&format!("Back {} change(s)", count));
+        }
+    }
+
+    // "Later": symmetric re-application, walking the most recently created
+    // child while its revision falls within `window`.
+    fn later(&mut self, window: Duration) {
+        let cutoff = SystemTime::now().checked_sub(window).unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut count = 0;
+        loop {
+            let Some(&child) = self.revisions[self.current_revision].children.last() else {
+                break;
+            };
+            if self.revisions[child].timestamp < cutoff {
+                break;
+            }
+            if self.redo_step().is_none() {
+                break;
+            }
+            count += 1;
+        }
+        if count == 0 {
+            self.status_message = "Nothing to redo in that window".to_string();
+        } else {
+            self.save_and_set_status(&format!("Forward {} change(s)", count));
         }
     }
 }
@@ -695,7 +1297,7 @@ fn default_task_template(lines: &[LineItem]) -> Task {
             return Task {
                 indent: task.indent.clone(),
                 bullet: task.bullet.clone(),
-                completed: false,
+                state: CheckState::Off,
                 text: String::new(),
             };
         }
@@ -703,7 +1305,7 @@ fn default_task_template(lines: &[LineItem]) -> Task {
     Task {
         indent: String::new(),
         bullet: "-".to_string(),
-        completed: false,
+        state: CheckState::Off,
         text: String::new(),
     }
 }
@@ -712,23 +1314,360 @@ fn is_modified(current: SystemTime, last: SystemTime) -> bool {
     current.duration_since(last).is_ok()
 }
 
-struct TerminalGuard;
+// Bounding byte range of the matched positions within `text`, for
+// substring-level highlighting.
+fn match_span(text: &str, positions: &[usize]) -> (usize, usize) {
+    let start = positions.iter().copied().min().unwrap_or(0);
+    let last = positions.iter().copied().max().unwrap_or(0);
+    let end = text[last..]
+        .chars()
+        .next()
+        .map(|c| last + c.len_utf8())
+        .unwrap_or(last);
+    (start, end)
+}
+
+type PanicHook = dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send + 'static;
+
+struct TerminalGuard {
+    // The hook in place before we installed ours, kept around (behind an
+    // `Arc` so our hook can also hold a reference) to chain into and to put
+    // back when the guard drops.
+    previous_hook: Arc<PanicHook>,
+    // True while temporarily left via `cleanup` (e.g. to shell out to
+    // `$EDITOR`) and not yet put back with `resume`.
+    suspended: bool,
+    // Whether this guard queued `EnableMouseCapture`; `resume` only
+    // re-enables it if so, and `restore_terminal` (reachable from the panic
+    // hook, which has no `self`) consults `MOUSE_CAPTURE_ON` for the same
+    // reason.
+    mouse_enabled: bool,
+}
+
+// Mirrors whether the live `TerminalGuard` has mouse capture on, so the
+// panic hook's `restore_terminal` (a free function with no `self`) knows
+// whether `DisableMouseCapture` is needed. Only ever written by
+// `TerminalGuard::with_mouse`, and there's only ever one guard per process.
+static MOUSE_CAPTURE_ON: AtomicBool = AtomicBool::new(false);
 
 impl TerminalGuard {
-    fn new() -> io::Result<Self> {
+    // Terminals that mangle mouse escape sequences can pass `false` to opt
+    // out; `run` wires this to the `--mouse` CLI flag.
+    fn with_mouse(mouse: bool) -> io::Result<Self> {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         stdout.execute(EnterAlternateScreen)?;
         stdout.execute(Hide)?;
-        Ok(Self)
+        if mouse {
+            stdout.execute(EnableMouseCapture)?;
+        }
+        MOUSE_CAPTURE_ON.store(mouse, Ordering::Relaxed);
+
+        // A panic unwinding past this guard's `Drop` (or aborting outright
+        // under `panic = "abort"`) would otherwise leave the terminal in
+        // raw/alt-screen/hidden-cursor state underneath a garbled backtrace.
+        // Restore it first, then hand off to whatever hook ran before ours.
+        let previous_hook: Arc<PanicHook> = Arc::from(std::panic::take_hook());
+        let chained = Arc::clone(&previous_hook);
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            chained(info);
+        }));
+
+        Ok(Self { previous_hook, suspended: false, mouse_enabled: mouse })
+    }
+
+    // Temporarily leave the alternate screen, disable raw mode, and show the
+    // cursor so a shelled-out child process (e.g. `$EDITOR`) gets a normal
+    // terminal instead of inheriting raw mode and a broken alt-screen.
+    // Idempotent: a second call while already suspended is a no-op.
+    fn cleanup(&mut self) {
+        if self.suspended {
+            return;
+        }
+        restore_terminal();
+        self.suspended = true;
+    }
+
+    // Undo `cleanup`: re-enable raw mode, re-enter the alternate screen, and
+    // hide the cursor. Idempotent: a call while not suspended is a no-op.
+    fn resume(&mut self) {
+        if !self.suspended {
+            return;
+        }
+        let _ = enable_raw_mode();
+        let mut stdout = io::stdout();
+        let _ = stdout.execute(EnterAlternateScreen);
+        let _ = stdout.execute(Hide);
+        if self.mouse_enabled {
+            let _ = stdout.execute(EnableMouseCapture);
+        }
+        self.suspended = false;
     }
 }
 
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
-        let _ = disable_raw_mode();
-        let mut stdout = io::stdout();
-        let _ = stdout.execute(LeaveAlternateScreen);
-        let _ = stdout.execute(Show);
+        // If we're mid-`cleanup` (e.g. the editor call panicked before
+        // `resume` ran), the screen is already in its normal state; leaving
+        // it again would be redundant.
+        if !self.suspended {
+            restore_terminal();
+        }
+        let previous = Arc::clone(&self.previous_hook);
+        std::panic::set_hook(Box::new(move |info| previous(info)));
+    }
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let mut stdout = io::stdout();
+    if MOUSE_CAPTURE_ON.load(Ordering::Relaxed) {
+        let _ = stdout.execute(DisableMouseCapture);
+    }
+    let _ = stdout.execute(LeaveAlternateScreen);
+    let _ = stdout.execute(Show);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> LineItem {
+        LineItem::Task(Task {
+            indent: String::new(),
+            bullet: "-".to_string(),
+            state: CheckState::Off,
+            text: text.to_string(),
+        })
+    }
+
+    fn task_texts(lines: &[LineItem]) -> Vec<&str> {
+        lines
+            .iter()
+            .filter_map(|l| match l {
+                LineItem::Task(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // `App::new` writes nothing on construction, but operator-pending motions
+    // that mutate `lines` (e.g. `dd`) go through `save_and_set_status`, which
+    // always does a real write - so tests need a real, writable path rather
+    // than a nonexistent one. Keeping the `TempDir` alive for the test's
+    // lifetime is what actually deletes it afterward.
+    fn test_app() -> (tempfile::TempDir, App) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("todo.md");
+        let app = App::new(path).expect("in-memory app");
+        (dir, app)
+    }
+
+    #[test]
+    fn dd_deletes_only_the_current_line() {
+        let (_dir, mut app) = test_app();
+        app.lines = vec![line("a"), line("b"), line("c")];
+        app.cursor = 1;
+        app.pending_operator = PendingOperator::Delete;
+
+        assert!(app.run_pending_operator(Key::Char('d')));
+
+        assert_eq!(task_texts(&app.lines), vec!["a", "c"]);
+        assert_eq!(app.pending_operator, PendingOperator::None);
+    }
+
+    #[test]
+    fn three_dd_deletes_three_lines_from_the_cursor() {
+        let (_dir, mut app) = test_app();
+        app.lines = vec![line("a"), line("b"), line("c"), line("d"), line("e")];
+        app.cursor = 0;
+        app.count_prefix = "3".to_string();
+        app.pending_operator = PendingOperator::Delete;
+
+        assert!(app.run_pending_operator(Key::Char('d')));
+
+        assert_eq!(task_texts(&app.lines), vec!["d", "e"]);
+    }
+
+    #[test]
+    fn dj_deletes_the_current_and_next_line() {
+        let (_dir, mut app) = test_app();
+        app.lines = vec![line("a"), line("b"), line("c")];
+        app.cursor = 0;
+        app.pending_operator = PendingOperator::Delete;
+
+        assert!(app.run_pending_operator(Key::Char('j')));
+
+        assert_eq!(task_texts(&app.lines), vec!["c"]);
+    }
+
+    #[test]
+    fn dgg_requires_a_second_g_before_deleting_to_the_top() {
+        let (_dir, mut app) = test_app();
+        app.lines = vec![line("a"), line("b"), line("c")];
+        app.cursor = 2;
+        app.pending_operator = PendingOperator::Delete;
+
+        // The first `g` only arms the two-key `gg` motion; the operator must
+        // still be pending and nothing deleted yet (this is the exact bug
+        // the maintainer flagged: a single `g` used to delete to line 0
+        // immediately).
+        assert!(app.run_pending_operator(Key::Char('g')));
+        assert_eq!(app.pending_operator, PendingOperator::Delete);
+        assert_eq!(task_texts(&app.lines), vec!["a", "b", "c"]);
+
+        assert!(app.run_pending_operator(Key::Char('g')));
+
+        assert_eq!(task_texts(&app.lines), Vec::<&str>::new());
+        assert_eq!(app.pending_operator, PendingOperator::None);
+    }
+
+    #[test]
+    fn a_non_g_key_after_the_first_g_cancels_instead_of_deleting_to_top() {
+        let (_dir, mut app) = test_app();
+        app.lines = vec![line("a"), line("b"), line("c")];
+        app.cursor = 2;
+        app.pending_operator = PendingOperator::Delete;
+
+        assert!(app.run_pending_operator(Key::Char('g')));
+        assert!(!app.run_pending_operator(Key::Char('x')));
+
+        assert_eq!(task_texts(&app.lines), vec!["a", "b", "c"]);
+        assert!(!app.pending_operator_g);
+    }
+
+    #[test]
+    fn unrecognized_key_is_not_consumed_and_leaves_lines_untouched() {
+        // `run_pending_operator` itself only reports "not consumed" here;
+        // `handle_normal_key` is what actually resets `pending_operator` on
+        // that `false`, so it's still `Delete` after this call.
+        let (_dir, mut app) = test_app();
+        app.lines = vec![line("a"), line("b")];
+        app.cursor = 0;
+        app.pending_operator = PendingOperator::Delete;
+
+        assert!(!app.run_pending_operator(Key::Esc));
+
+        assert_eq!(task_texts(&app.lines), vec!["a", "b"]);
+        assert_eq!(app.pending_operator, PendingOperator::Delete);
+    }
+
+    // Builds an edit on top of `app.current_revision` the way `handle_normal_key`
+    // would (undo-tracked mutation, then a real `save_and_set_status`), so
+    // `commit_revision` materializes a new child revision.
+    fn commit_edit(app: &mut App, lines: Vec<LineItem>) {
+        app.save_undo_state();
+        app.lines = lines;
+        app.save_and_set_status("edit");
+    }
+
+    #[test]
+    fn undo_then_redo_returns_to_the_same_revision() {
+        let (_dir, mut app) = test_app();
+        commit_edit(&mut app, vec![line("a")]);
+        commit_edit(&mut app, vec![line("a"), line("b")]);
+        assert_eq!(app.current_revision, 2);
+
+        assert!(app.undo_step().is_some());
+        assert_eq!(app.current_revision, 1);
+        assert_eq!(task_texts(&app.lines), vec!["a"]);
+
+        assert!(app.redo_step().is_some());
+        assert_eq!(app.current_revision, 2);
+        assert_eq!(task_texts(&app.lines), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn undo_root_has_nothing_to_undo() {
+        let (_dir, mut app) = test_app();
+        assert_eq!(app.current_revision, 0);
+        assert!(app.undo_step().is_none());
+        assert_eq!(app.current_revision, 0);
+    }
+
+    #[test]
+    fn redo_after_a_new_edit_follows_the_newest_branch_not_the_undone_one() {
+        // Mirrors vim/Helix's undo-tree behavior: editing after an undo
+        // starts a new branch instead of discarding the one that was undone,
+        // and `redo` always walks into the most recently created child.
+        let (_dir, mut app) = test_app();
+        commit_edit(&mut app, vec![line("a")]);
+        commit_edit(&mut app, vec![line("a"), line("b")]); // revision 2, the "undone" branch
+        app.undo_step();
+        assert_eq!(app.current_revision, 1);
+
+        commit_edit(&mut app, vec![line("a"), line("c")]); // revision 3, a sibling of 2
+        assert_eq!(app.current_revision, 3);
+        assert_eq!(app.revisions[1].children, vec![2, 3]);
+
+        app.undo_step();
+        assert_eq!(app.current_revision, 1);
+        assert!(app.redo_step().is_some());
+
+        assert_eq!(app.current_revision, 3);
+        assert_eq!(task_texts(&app.lines), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn earlier_stops_undoing_once_it_reaches_a_revision_outside_the_window() {
+        let now = SystemTime::now();
+        let (_dir, mut app) = test_app();
+        // Backdate the first edit's revision directly rather than waiting in
+        // real time for it to age out of the window.
+        app.revisions.push(Revision {
+            lines: vec![line("a")],
+            cursor: 0,
+            timestamp: now - Duration::from_secs(120),
+            parent: Some(0),
+            children: Vec::new(),
+        });
+        app.revisions[0].children.push(1);
+        app.revisions.push(Revision {
+            lines: vec![line("a"), line("b")],
+            cursor: 0,
+            timestamp: now,
+            parent: Some(1),
+            children: Vec::new(),
+        });
+        app.revisions[1].children.push(2);
+        app.current_revision = 2;
+        app.lines = vec![line("a"), line("b")];
+
+        app.earlier(Duration::from_secs(60));
+
+        // Only the edit committed "now" falls inside the 60s window; the one
+        // backdated 120s stops the walk before it's undone too.
+        assert_eq!(app.current_revision, 1);
+        assert_eq!(task_texts(&app.lines), vec!["a"]);
+    }
+
+    #[test]
+    fn later_redoes_the_child_revision_within_the_window() {
+        let now = SystemTime::now();
+        let (_dir, mut app) = test_app();
+        app.revisions.push(Revision {
+            lines: vec![line("a")],
+            cursor: 0,
+            timestamp: now - Duration::from_secs(120),
+            parent: Some(0),
+            children: Vec::new(),
+        });
+        app.revisions[0].children.push(1);
+        app.revisions.push(Revision {
+            lines: vec![line("a"), line("b")],
+            cursor: 0,
+            timestamp: now,
+            parent: Some(1),
+            children: Vec::new(),
+        });
+        app.revisions[1].children.push(2);
+        app.current_revision = 1;
+        app.lines = vec![line("a")];
+
+        app.later(Duration::from_secs(60));
+
+        assert_eq!(app.current_revision, 2);
+        assert_eq!(task_texts(&app.lines), vec!["a", "b"]);
     }
 }
@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use crate::model::{CheckState, LineItem};
+
+// The run of lines after `idx` holding all of its descendants (children,
+// grandchildren, ...), plus any blank/raw lines among or after them.
+// Indentation resets at the first sibling-or-shallower task, or at a section
+// boundary.
+pub fn descendant_range(lines: &[LineItem], idx: usize) -> Range<usize> {
+    let base_indent = match lines.get(idx) {
+        Some(LineItem::Task(task)) => task.indent.len(),
+        _ => return idx + 1..idx + 1,
+    };
+    let mut end = idx + 1;
+    while let Some(line) = lines.get(end) {
+        match line {
+            LineItem::Section { .. } => break,
+            LineItem::Task(task) if task.indent.len() <= base_indent => break,
+            // Blank/raw lines don't end the run, as `find_parent` skips them.
+            _ => end += 1,
+        }
+    }
+    idx + 1..end
+}
+
+// Set `idx` and all of its descendants to `state` (parent toggling cascades
+// down to children, as in org-mode).
+pub fn set_state_cascade(lines: &mut [LineItem], idx: usize, state: CheckState) {
+    if let Some(LineItem::Task(task)) = lines.get_mut(idx) {
+        task.state = state;
+    }
+    for i in descendant_range(lines, idx) {
+        if let LineItem::Task(task) = &mut lines[i] {
+            task.state = state;
+        }
+    }
+}
+
+// Walk up from `idx`, recomputing each ancestor's state from its children:
+// `On` if all children are on, `Off` if none are, `Partial` otherwise.
+pub fn recompute_ancestors(lines: &mut [LineItem], idx: usize) {
+    let mut child_indent = match lines.get(idx) {
+        Some(LineItem::Task(task)) => task.indent.len(),
+        _ => return,
+    };
+    let mut pos = idx;
+
+    loop {
+        let parent_idx = find_parent(lines, pos, child_indent);
+        let Some(parent_idx) = parent_idx else {
+            break;
+        };
+
+        let range = descendant_range(lines, parent_idx);
+        let mut any_on = false;
+        let mut all_on = true;
+        for i in range {
+            if let LineItem::Task(task) = &lines[i] {
+                match task.state {
+                    CheckState::On => any_on = true,
+                    CheckState::Partial => {
+                        any_on = true;
+                        all_on = false;
+                    }
+                    CheckState::Off => all_on = false,
+                }
+            }
+        }
+        let new_state = if all_on {
+            CheckState::On
+        } else if any_on {
+            CheckState::Partial
+        } else {
+            CheckState::Off
+        };
+
+        let parent_indent = match &lines[parent_idx] {
+            LineItem::Task(task) => task.indent.len(),
+            _ => break,
+        };
+        if let LineItem::Task(task) = &mut lines[parent_idx] {
+            task.state = new_state;
+        }
+
+        pos = parent_idx;
+        child_indent = parent_indent;
+    }
+}
+
+fn find_parent(lines: &[LineItem], pos: usize, child_indent: usize) -> Option<usize> {
+    let mut i = pos;
+    while i > 0 {
+        i -= 1;
+        match &lines[i] {
+            LineItem::Section { .. } => return None,
+            LineItem::Task(task) if task.indent.len() < child_indent => return Some(i),
+            _ => continue,
+        }
+    }
+    None
+}
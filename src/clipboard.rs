@@ -0,0 +1,45 @@
+use arboard::Clipboard;
+
+use crate::format::{render_line, Format};
+use crate::io::parse_lines;
+use crate::model::LineItem;
+
+// Mirrors Helix's ClipboardType split: yanks always land in the internal
+// register so cut/paste keeps working even when no system clipboard is
+// reachable (headless session, missing X11/Wayland selection owner, etc.),
+// and we best-effort mirror the same text out to the OS clipboard.
+pub fn set_system_clipboard(text: &str) -> Result<(), String> {
+    Clipboard::new()
+        .and_then(|mut cb| cb.set_text(text.to_string()))
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_system_clipboard() -> Result<String, String> {
+    Clipboard::new()
+        .and_then(|mut cb| cb.get_text())
+        .map_err(|e| e.to_string())
+}
+
+// Serialize yanked items through the active format's rendering so pasted
+// content round-trips through the file on disk.
+pub fn serialize(items: &[LineItem], format: &dyn Format) -> String {
+    items
+        .iter()
+        .map(|item| render_line(item, format))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Parse clipboard text back into tasks/sections, falling back to
+// `default_indent` for any task line that didn't carry its own indent.
+pub fn parse(text: &str, default_indent: &str, format: &dyn Format) -> Vec<LineItem> {
+    let mut items = parse_lines(text, format);
+    for item in &mut items {
+        if let LineItem::Task(task) = item {
+            if task.indent.is_empty() {
+                task.indent = default_indent.to_string();
+            }
+        }
+    }
+    items
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use crate::fuzzy::fuzzy_match;
+use crate::model::{App, CompletionState, LineItem};
+
+// How many candidates the popup shows at once.
+const MAX_CANDIDATES: usize = 6;
+
+// The kinds of tokens the popup completes, detected by the marker
+// immediately left of the cursor.
+enum TokenKind {
+    Tag,
+    Mention,
+    Due,
+}
+
+impl App {
+    // Recomputes `self.completion` from the token immediately left of the
+    // text input's cursor. Called after every edit-mode keystroke that can
+    // change the text or move the cursor; clears the popup once the cursor
+    // no longer sits inside a completable token.
+    pub(crate) fn update_completion(&mut self) {
+        let value = self.text_input.value().to_string();
+        let cursor = self.text_input.cursor();
+        let Some((kind, token_start, partial)) = current_token(&value, cursor) else {
+            self.completion = None;
+            return;
+        };
+
+        let candidates = self.matching_candidates(&kind, &partial);
+        if candidates.is_empty() {
+            self.completion = None;
+            return;
+        }
+
+        let selected = match &self.completion {
+            Some(existing) if existing.token_start == token_start => {
+                existing.selected.min(candidates.len() - 1)
+            }
+            _ => 0,
+        };
+
+        self.completion = Some(CompletionState {
+            token_start,
+            candidates,
+            selected,
+        });
+    }
+
+    // Distinct tokens of `kind` already present in `self.lines`, fuzzy
+    // filtered by `partial` and sorted by match quality (best first).
+    fn matching_candidates(&self, kind: &TokenKind, partial: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut scored: Vec<(i64, String)> = Vec::new();
+        for line in &self.lines {
+            let LineItem::Task(task) = line else { continue };
+            for token in tokens_of_kind(&task.text, kind) {
+                if !seen.insert(token.clone()) {
+                    continue;
+                }
+                if let Some((score, _)) = fuzzy_match(&token, partial) {
+                    scored.push((score, token));
+                }
+            }
+        }
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        scored.into_iter().take(MAX_CANDIDATES).map(|(_, token)| token).collect()
+    }
+
+    // Moves the selected candidate by `delta`, wrapping around.
+    pub(crate) fn completion_select(&mut self, delta: isize) {
+        let Some(completion) = &mut self.completion else { return };
+        let len = completion.candidates.len() as isize;
+        completion.selected = (completion.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    // Replaces the partial token with the selected candidate and dismisses
+    // the popup.
+    pub(crate) fn accept_completion(&mut self) {
+        let Some(completion) = self.completion.take() else { return };
+        let Some(candidate) = completion.candidates.get(completion.selected) else { return };
+        let end = self.text_input.cursor();
+        self.text_input.replace_range(completion.token_start, end, candidate);
+    }
+
+    pub(crate) fn dismiss_completion(&mut self) {
+        self.completion = None;
+    }
+}
+
+// Finds the token immediately left of `cursor` in `value`, if it looks like
+// a `#tag`, `@mention`, or `due:` token. Returns its kind, byte start
+// offset, and the text typed so far (markers included, so it can be
+// fuzzy-matched directly against full candidate tokens, which also include
+// their marker).
+fn current_token(value: &str, cursor: usize) -> Option<(TokenKind, usize, String)> {
+    let before = &value[..cursor.min(value.len())];
+    let start = before
+        .rfind(|c: char| c.is_whitespace())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let token = &before[start..];
+
+    if token.starts_with('#') {
+        return Some((TokenKind::Tag, start, token.to_string()));
+    }
+    if token.starts_with('@') {
+        return Some((TokenKind::Mention, start, token.to_string()));
+    }
+    if token.starts_with("due:") {
+        return Some((TokenKind::Due, start, token.to_string()));
+    }
+    None
+}
+
+// Every whitespace-delimited word in `text` that matches `kind`'s marker.
+fn tokens_of_kind(text: &str, kind: &TokenKind) -> Vec<String> {
+    text.split_whitespace()
+        .filter(|word| match kind {
+            TokenKind::Tag => word.starts_with('#') && word.len() > 1,
+            TokenKind::Mention => word.starts_with('@') && word.len() > 1,
+            TokenKind::Due => word.starts_with("due:") && word.len() > 4,
+        })
+        .map(|word| word.to_string())
+        .collect()
+}
@@ -1,4 +1,4 @@
-use crate::model::{App, EditIntent, EditTarget, LineItem, Mode, Task, INDENT_LEVELS};
+use crate::model::{App, CheckState, EditIntent, EditTarget, LineItem, Mode, Task, INDENT_LEVELS};
 
 impl App {
     pub fn start_edit_current(&mut self) {
@@ -8,7 +8,7 @@ impl App {
         match self.lines.get(self.cursor) {
             Some(LineItem::Section { .. }) => self.start_edit_section(),
             Some(LineItem::Task(_)) => self.start_edit_task(),
-            None => {}
+            _ => {}
         }
     }
 
@@ -28,6 +28,7 @@ impl App {
         self.input_placeholder = "Describe the task".to_string();
         self.text_input.set_value(text);
         self.edit_index = Some(self.cursor);
+        self.completion = None;
         self.status_message = "Editing current task".to_string();
     }
 
@@ -36,7 +37,7 @@ impl App {
             return;
         }
         let title = match self.lines.get(self.cursor) {
-            Some(LineItem::Section { title }) => title.clone(),
+            Some(LineItem::Section { title, .. }) => title.clone(),
             _ => return,
         };
 
@@ -47,6 +48,7 @@ impl App {
         self.input_placeholder = "Section title".to_string();
         self.text_input.set_value(title);
         self.edit_index = Some(self.cursor);
+        self.completion = None;
         self.status_message = "Editing section".to_string();
     }
 
@@ -68,6 +70,7 @@ impl App {
         self.input_placeholder = "Describe the task".to_string();
         self.status_message = "New task".to_string();
         self.edit_template = template;
+        self.completion = None;
     }
 
     pub fn start_insert_section_at(&mut self, index: usize) {
@@ -81,6 +84,7 @@ impl App {
         self.text_input.reset();
         self.input_placeholder = "Section title".to_string();
         self.status_message = "New section".to_string();
+        self.completion = None;
     }
 
     pub fn apply_current_edit(&mut self, value: &str) {
@@ -90,7 +94,8 @@ impl App {
                     if let Some(idx) = self.edit_index {
                         if matches!(self.lines.get(idx), Some(LineItem::Section { .. })) {
                             self.save_undo_state();
-                            if let Some(LineItem::Section { title }) = self.lines.get_mut(idx) {
+                            if let Some(LineItem::Section { title, .. }) = self.lines.get_mut(idx)
+                            {
                                 *title = value.to_string();
                             }
                         }
@@ -99,6 +104,7 @@ impl App {
                 EditIntent::Insert => {
                     let new_section = LineItem::Section {
                         title: value.to_string(),
+                        cookie: None,
                     };
                     let idx = clamp_index(self.insert_index.unwrap_or(0), self.lines.len());
                     self.save_undo_state();
@@ -120,7 +126,7 @@ impl App {
                     let new_task = LineItem::Task(Task {
                         indent: self.edit_template.indent.clone(),
                         bullet: self.edit_template.bullet.clone(),
-                        completed: false,
+                        state: CheckState::Off,
                         text: value.to_string(),
                     });
                     self.lines.insert(idx, new_task);
@@ -140,6 +146,7 @@ impl App {
         self.insert_index = None;
         self.cursor = clamp_cursor(self.cursor, self.lines.len());
         self.text_input.reset();
+        self.completion = None;
         self.normalize_selection();
     }
 
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::model::{CheckState, CookieKind, LineItem, Task};
+
+// A pluggable on-disk syntax. Blank-line handling lives in `io` since it's
+// the same for every format; a `Format` only needs to know how to turn a
+// single non-blank line into a `LineItem` and back.
+pub trait Format: std::fmt::Debug {
+    fn parse_line(&self, line: &str) -> LineItem;
+    fn render_task(&self, task: &Task) -> String;
+    // `cookie_text` is the already-formatted bracketed suffix (`[2/5]`,
+    // `[/]`, ...), or `None` for a section with no statistics cookie.
+    fn render_section(&self, title: &str, cookie_text: Option<&str>) -> String;
+}
+
+// Context-free rendering: for a section with a cookie this re-emits the
+// placeholder form (`[/]`/`[%]`) rather than computed numbers, since
+// computing real numbers needs the sibling tasks that follow it. Used by
+// anything that renders a single item in isolation (e.g. the clipboard);
+// `io::save_lines` renders with the real counts instead.
+pub fn render_line(item: &LineItem, format: &dyn Format) -> String {
+    match item {
+        LineItem::Task(task) => format.render_task(task),
+        LineItem::Section { title, cookie } => {
+            let cookie_text = cookie.map(|kind| match kind {
+                CookieKind::Fraction => "[/]".to_string(),
+                CookieKind::Percent => "[%]".to_string(),
+            });
+            format.render_section(title, cookie_text.as_deref())
+        }
+        LineItem::Raw { text } => text.clone(),
+        LineItem::Blank => String::new(),
+    }
+}
+
+// Picks a format from the file's extension: `.org` gets the org-mode
+// backend, everything else (including no extension) gets GFM. This is what
+// makes loading one format and saving another possible — rename the file
+// and the next load picks up the other syntax.
+pub fn format_for_path(path: &Path) -> Box<dyn Format> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("org") => Box::new(OrgFormat),
+        _ => Box::new(GfmFormat),
+    }
+}
+
+// Matches a trailing statistics cookie shared by both formats: either a
+// placeholder (`[/]`, `[%]`) or an already-computed one (`[2/5]`, `[40%]`).
+static COOKIE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(.*?)\s*\[(?:(\d+)/(\d+)|/|(\d+)%|%)\]\s*$").expect("valid cookie regex")
+});
+
+fn split_cookie_suffix(raw: &str) -> (String, Option<CookieKind>) {
+    if let Some(caps) = COOKIE_RE.captures(raw) {
+        let title = caps.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
+        let kind = if caps.get(2).is_some() || caps.get(3).is_some() {
+            CookieKind::Fraction
+        } else if caps.get(4).is_some() {
+            CookieKind::Percent
+        } else if raw.trim_end().ends_with("[/]") {
+            CookieKind::Fraction
+        } else {
+            CookieKind::Percent
+        };
+        return (title, Some(kind));
+    }
+    (raw.to_string(), None)
+}
+
+// --- GitHub-flavored markdown: `## ` section headers, `- [ ]`/`[x]`/`[-]` items ---
+
+static GFM_SECTION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^##\s+(.*)$").expect("valid section regex"));
+static GFM_CHECKBOX_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\s*)([-*])\s+\[([ xX\-])\]\s*(.*)$").expect("valid checkbox regex")
+});
+
+#[derive(Debug)]
+pub struct GfmFormat;
+
+impl Format for GfmFormat {
+    fn parse_line(&self, line: &str) -> LineItem {
+        if let Some(caps) = GFM_SECTION_RE.captures(line) {
+            let raw = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+            let (title, cookie) = split_cookie_suffix(raw);
+            return LineItem::Section { title, cookie };
+        }
+        if let Some(caps) = GFM_CHECKBOX_RE.captures(line) {
+            let indent = caps.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
+            let bullet = caps.get(2).map(|m| m.as_str()).unwrap_or("-").to_string();
+            let mark = caps.get(3).map(|m| m.as_str()).unwrap_or(" ");
+            let text = caps.get(4).map(|m| m.as_str()).unwrap_or("").to_string();
+            return LineItem::Task(Task {
+                indent,
+                bullet,
+                state: CheckState::from_mark(mark),
+                text,
+            });
+        }
+        LineItem::Raw {
+            text: line.to_string(),
+        }
+    }
+
+    fn render_task(&self, task: &Task) -> String {
+        format!(
+            "{}{} [{}] {}",
+            task.indent,
+            task.bullet,
+            task.state.mark(),
+            task.text
+        )
+    }
+
+    fn render_section(&self, title: &str, cookie_text: Option<&str>) -> String {
+        match cookie_text {
+            Some(cookie) => format!("## {} {}", title, cookie),
+            None => format!("## {}", title),
+        }
+    }
+}
+
+// --- org-mode: `* ` headlines, `- [ ]`/`[-]`/`[X]` list items, TODO/DONE keywords ---
+
+static ORG_HEADLINE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\*\s+(.*)$").expect("valid org headline regex"));
+static ORG_CHECKBOX_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\s*)-\s+\[([ xX\-])\]\s*(?:(TODO|DONE)\s+)?(.*)$")
+        .expect("valid org checkbox regex")
+});
+
+#[derive(Debug)]
+pub struct OrgFormat;
+
+impl Format for OrgFormat {
+    fn parse_line(&self, line: &str) -> LineItem {
+        if let Some(caps) = ORG_HEADLINE_RE.captures(line) {
+            let raw = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+            let (title, cookie) = split_cookie_suffix(raw);
+            return LineItem::Section { title, cookie };
+        }
+        if let Some(caps) = ORG_CHECKBOX_RE.captures(line) {
+            let indent = caps.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
+            let mark = caps.get(2).map(|m| m.as_str()).unwrap_or(" ");
+            let text = caps.get(4).map(|m| m.as_str()).unwrap_or("").to_string();
+            return LineItem::Task(Task {
+                indent,
+                bullet: "-".to_string(),
+                state: CheckState::from_mark(mark),
+                text,
+            });
+        }
+        LineItem::Raw {
+            text: line.to_string(),
+        }
+    }
+
+    fn render_task(&self, task: &Task) -> String {
+        let keyword = if task.state.is_on() { "DONE" } else { "TODO" };
+        format!(
+            "{}- [{}] {} {}",
+            task.indent,
+            task.state.mark(),
+            keyword,
+            task.text
+        )
+    }
+
+    fn render_section(&self, title: &str, cookie_text: Option<&str>) -> String {
+        match cookie_text {
+            Some(cookie) => format!("* {} {}", title, cookie),
+            None => format!("* {}", title),
+        }
+    }
+}
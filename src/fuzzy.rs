@@ -0,0 +1,105 @@
+use regex::Regex;
+
+// Case-insensitive unless `query` itself contains an uppercase letter
+// (ripgrep/vim's smart-case convention), otherwise a plain substring search.
+// Returns the leftmost match, scored so earlier matches sort first.
+pub fn smart_case_match(candidate: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let case_sensitive = query.chars().any(|c| c.is_uppercase());
+    let fold = |c: char| if case_sensitive { c } else { c.to_lowercase().next().unwrap_or(c) };
+
+    let chars: Vec<(usize, char)> = candidate.char_indices().collect();
+    let query_folded: Vec<char> = query.chars().map(fold).collect();
+    let qlen = query_folded.len();
+    if chars.len() < qlen {
+        return None;
+    }
+
+    for start in 0..=(chars.len() - qlen) {
+        let is_match = (0..qlen).all(|i| fold(chars[start + i].1) == query_folded[i]);
+        if !is_match {
+            continue;
+        }
+        let positions: Vec<usize> = chars[start..start + qlen].iter().map(|&(i, _)| i).collect();
+        return Some((-(chars[start].0 as i64), positions));
+    }
+    None
+}
+
+// Matches `candidate` against a pre-compiled regex, scored so a match
+// earlier in the string sorts first.
+pub fn regex_match(re: &Regex, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let m = re.find(candidate)?;
+    let positions: Vec<usize> = candidate[m.start()..m.end()]
+        .char_indices()
+        .map(|(i, _)| m.start() + i)
+        .collect();
+    Some((-(m.start() as i64), positions))
+}
+
+// A self-contained fuzzy subsequence matcher, modeled on Helix's picker
+// fuzzy matching: the query must match as an in-order subsequence of the
+// candidate, scored to favor consecutive runs, word-boundary starts, and a
+// match at the very beginning of the string.
+pub fn fuzzy_match(candidate: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let chars: Vec<(usize, char)> = candidate.char_indices().collect();
+    let query_lower: Vec<char> = query.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    let mut positions = Vec::with_capacity(query_lower.len());
+    let mut qi = 0usize;
+    let mut last_match_idx: Option<usize> = None;
+    let mut consecutive_run = 0i64;
+    let mut score: i64 = 0;
+
+    for (char_idx, &(byte_idx, ch)) in chars.iter().enumerate() {
+        if qi >= query_lower.len() {
+            break;
+        }
+        if ch.to_ascii_lowercase() != query_lower[qi] {
+            continue;
+        }
+
+        let is_boundary = char_idx == 0
+            || matches!(chars[char_idx - 1].1, ' ' | '-' | '_' | '/' | '.')
+            || (chars[char_idx - 1].1.is_lowercase() && ch.is_uppercase());
+
+        match last_match_idx {
+            Some(prev) if char_idx == prev + 1 => {
+                consecutive_run += 1;
+                score += 5 + consecutive_run;
+            }
+            Some(prev) => {
+                consecutive_run = 0;
+                score -= (char_idx - prev - 1) as i64;
+            }
+            None => {
+                consecutive_run = 0;
+                score -= char_idx as i64 / 2;
+            }
+        }
+
+        if is_boundary {
+            score += 8;
+        }
+        if char_idx == 0 {
+            score += 10;
+        }
+
+        positions.push(byte_idx);
+        last_match_idx = Some(char_idx);
+        qi += 1;
+    }
+
+    if qi < query_lower.len() {
+        return None;
+    }
+
+    Some((score, positions))
+}
@@ -0,0 +1,265 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Borrowed from Helix's NumberIncrementor/DateTimeIncrementor: find the token
+// enclosing a column and bump it in place, preserving width/format.
+
+static NUMBER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"-?\d+").expect("valid number regex"));
+static DATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d{4}-\d{2}-\d{2}").expect("valid date regex"));
+static TIME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d{2}:\d{2}(:\d{2})?").expect("valid time regex"));
+
+enum TokenKind {
+    Date,
+    Time,
+    Number,
+}
+
+// Bump the number or date/time token under `col` (a byte offset into `text`)
+// by `delta * count`. Returns the replacement text, or `None` if no token is
+// under the cursor.
+//
+// Candidates are gathered from all three kinds and the leftmost-starting one
+// wins, rather than always preferring dates over times over numbers: a date
+// match always starts at or before any number match nested inside it (e.g.
+// the "03" in "2024-03-01"), so this still resolves a cursor genuinely
+// inside a date to the date. But when `col` doesn't land inside any match —
+// which callers with no real horizontal cursor (see `App::bump_under_cursor`)
+// always hit by passing `col: 0` — a fixed date-first priority would make
+// the first date *anywhere* in the text win even when a number token
+// appears earlier; taking the overall leftmost match instead targets
+// whichever token actually comes first in reading order.
+pub fn bump_at_cursor(text: &str, col: usize, delta: i64, count: i64) -> Option<String> {
+    let col = col.min(text.len());
+    let amount = delta.saturating_mul(count.max(1));
+
+    let candidates = [
+        find_enclosing(&DATE_RE, text, col).map(|span| (span, TokenKind::Date)),
+        find_enclosing(&TIME_RE, text, col).map(|span| (span, TokenKind::Time)),
+        find_enclosing(&NUMBER_RE, text, col).map(|span| (span, TokenKind::Number)),
+    ];
+
+    let mut best: Option<((usize, usize), TokenKind)> = None;
+    for candidate in candidates {
+        let Some(((start, end), kind)) = candidate else {
+            continue;
+        };
+        let better = match &best {
+            Some(((best_start, _), _)) => start < *best_start,
+            None => true,
+        };
+        if better {
+            best = Some(((start, end), kind));
+        }
+    }
+
+    let ((start, end), kind) = best?;
+    match kind {
+        TokenKind::Date => bump_date(text, start, end, col, amount),
+        TokenKind::Time => bump_time(text, start, end, col, amount),
+        TokenKind::Number => bump_number(text, start, end, amount),
+    }
+}
+
+// Find the match from `re` whose span contains `col`, falling back to the
+// first match that starts at or after `col` so a cursor just before a token
+// still targets it.
+fn find_enclosing(re: &Regex, text: &str, col: usize) -> Option<(usize, usize)> {
+    let mut fallback = None;
+    for m in re.find_iter(text) {
+        if m.start() <= col && col < m.end() {
+            return Some((m.start(), m.end()));
+        }
+        if fallback.is_none() && m.start() >= col {
+            fallback = Some((m.start(), m.end()));
+        }
+    }
+    fallback
+}
+
+fn bump_number(text: &str, start: usize, end: usize, amount: i64) -> Option<String> {
+    let token = &text[start..end];
+    let negative = token.starts_with('-');
+    let digits = if negative { &token[1..] } else { token };
+    let width = digits.len();
+    let value: i64 = token.parse().ok()?;
+    let bumped = value + amount;
+
+    let rendered = if bumped < 0 {
+        format!("-{:0width$}", bumped.unsigned_abs(), width = width)
+    } else {
+        format!("{:0width$}", bumped, width = width)
+    };
+
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..start]);
+    out.push_str(&rendered);
+    out.push_str(&text[end..]);
+    Some(out)
+}
+
+fn bump_date(text: &str, start: usize, end: usize, col: usize, amount: i64) -> Option<String> {
+    let token = &text[start..end];
+    let mut parts = token.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+
+    // Column offsets within "YYYY-MM-DD" determine which field the cursor is in.
+    let offset = col.saturating_sub(start);
+    let (mut year, mut month, mut day) = (year, month, day);
+    if offset <= 3 {
+        year += amount;
+        let max_day = days_in_month(year, month);
+        if day > max_day {
+            day = max_day;
+        }
+    } else if (5..=6).contains(&offset) {
+        month += amount;
+        while month > 12 {
+            month -= 12;
+            year += 1;
+        }
+        while month < 1 {
+            month += 12;
+            year -= 1;
+        }
+        let max_day = days_in_month(year, month);
+        if day > max_day {
+            day = max_day;
+        }
+    } else {
+        day += amount;
+        loop {
+            let max_day = days_in_month(year, month) as i64;
+            if day > max_day {
+                day -= max_day;
+                month += 1;
+                if month > 12 {
+                    month = 1;
+                    year += 1;
+                }
+            } else if day < 1 {
+                month -= 1;
+                if month < 1 {
+                    month = 12;
+                    year -= 1;
+                }
+                day += days_in_month(year, month) as i64;
+            } else {
+                break;
+            }
+        }
+    }
+
+    let rendered = format!("{:04}-{:02}-{:02}", year, month, day);
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..start]);
+    out.push_str(&rendered);
+    out.push_str(&text[end..]);
+    Some(out)
+}
+
+fn bump_time(text: &str, start: usize, end: usize, col: usize, amount: i64) -> Option<String> {
+    let token = &text[start..end];
+    let fields: Vec<&str> = token.split(':').collect();
+    let mut hour: i64 = fields.first()?.parse().ok()?;
+    let mut minute: i64 = fields.get(1)?.parse().ok()?;
+    let mut second: i64 = fields.get(2).map(|s| s.parse().ok()).unwrap_or(Some(0))?;
+
+    let offset = col.saturating_sub(start);
+    if offset <= 2 {
+        hour = ((hour + amount) % 24 + 24) % 24;
+    } else if (3..=5).contains(&offset) {
+        minute += amount;
+        hour += minute.div_euclid(60);
+        minute = minute.rem_euclid(60);
+        hour = ((hour % 24) + 24) % 24;
+    } else {
+        second += amount;
+        minute += second.div_euclid(60);
+        second = second.rem_euclid(60);
+        hour += minute.div_euclid(60);
+        minute = minute.rem_euclid(60);
+        hour = ((hour % 24) + 24) % 24;
+    }
+
+    let rendered = if fields.len() == 3 {
+        format!("{:02}:{:02}:{:02}", hour, minute, second)
+    } else {
+        format!("{:02}:{:02}", hour, minute)
+    };
+
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..start]);
+    out.push_str(&rendered);
+    out.push_str(&text[end..]);
+    Some(out)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            if is_leap_year(year) {
+                29
+            } else {
+                28
+            }
+        }
+        _ => 30,
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumps_leftmost_token_when_no_real_column() {
+        // Regression test: with col: 0 (Normal mode has no horizontal
+        // cursor), the date used to always win over an earlier number
+        // because DATE_RE was tried first regardless of position.
+        let text = "Buy 3 apples, review by 2024-03-01";
+        assert_eq!(
+            bump_at_cursor(text, 0, 1, 1).as_deref(),
+            Some("Buy 4 apples, review by 2024-03-01")
+        );
+    }
+
+    #[test]
+    fn bumps_enclosing_date_rather_than_the_number_nested_inside_it() {
+        let text = "due 2024-03-01";
+        // col 13 sits in the day field ("01"); the date match's start (4)
+        // is still earlier than any number nested inside it, so the date
+        // wins and the day field gets bumped rather than a bare "01".
+        assert_eq!(
+            bump_at_cursor(text, 13, 1, 1).as_deref(),
+            Some("due 2024-03-02")
+        );
+    }
+
+    #[test]
+    fn date_rollover_carries_into_month_and_year() {
+        assert_eq!(
+            bump_at_cursor("2024-12-31", 9, 1, 1).as_deref(),
+            Some("2025-01-01")
+        );
+    }
+
+    #[test]
+    fn time_rollover_wraps_hour() {
+        assert_eq!(bump_at_cursor("23:59", 0, 1, 1).as_deref(), Some("00:59"));
+    }
+
+    #[test]
+    fn no_token_returns_none() {
+        assert_eq!(bump_at_cursor("no numbers here", 0, 1, 1), None);
+    }
+}
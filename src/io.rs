@@ -2,18 +2,37 @@ use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
 
-use once_cell::sync::Lazy;
-use regex::Regex;
+use crate::format::{render_line, Format};
+use crate::model::{CookieKind, LineItem};
 
-use crate::model::{LineItem, Task};
-
-static CHECKBOX_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^(\s*)([-*])\s+\[([ xX])\]\s*(.*)$").expect("valid checkbox regex"));
+// Non-destructive parsing: section headers and checkbox tasks are recognized
+// (per `format`'s syntax) and become structured `LineItem`s; everything else
+// (blank lines, prose, code fences, plain bullets, ...) is kept verbatim as
+// `Raw`/`Blank` so it round-trips unchanged through `save_lines`.
+pub(crate) fn parse_lines(data: &str, format: &dyn Format) -> Vec<LineItem> {
+    let normalized = data.replace('\r', "");
+    let mut raw_lines: Vec<&str> = normalized.split('\n').collect();
+    if normalized.ends_with('\n') {
+        // The trailing split element is an artifact of the final newline,
+        // not a blank line in the document; `save_lines` adds it back.
+        raw_lines.pop();
+    }
 
-static SECTION_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^##\s+(.*)$").expect("valid section regex"));
+    let mut items = Vec::new();
+    for line in raw_lines {
+        if line.trim().is_empty() {
+            items.push(LineItem::Blank);
+            continue;
+        }
+        items.push(format.parse_line(line));
+    }
+    items
+}
 
-pub fn load_lines(path: &Path) -> Result<(Vec<LineItem>, SystemTime), std::io::Error> {
+pub fn load_lines(
+    path: &Path,
+    format: &dyn Format,
+) -> Result<(Vec<LineItem>, SystemTime), std::io::Error> {
     let data = match fs::read_to_string(path) {
         Ok(contents) => contents,
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
@@ -22,31 +41,7 @@ pub fn load_lines(path: &Path) -> Result<(Vec<LineItem>, SystemTime), std::io::E
         Err(err) => return Err(err),
     };
 
-    // Destructive parsing: only section headers and checkbox tasks are retained.
-    let normalized = data.replace('\r', "");
-    let mut items = Vec::new();
-    for line in normalized.split('\n') {
-        if line.trim().is_empty() {
-            continue;
-        }
-        if let Some(caps) = SECTION_RE.captures(line) {
-            let title = caps.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
-            items.push(LineItem::Section { title });
-            continue;
-        }
-        if let Some(caps) = CHECKBOX_RE.captures(line) {
-            let indent = caps.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
-            let bullet = caps.get(2).map(|m| m.as_str()).unwrap_or("-").to_string();
-            let mark = caps.get(3).map(|m| m.as_str()).unwrap_or(" ");
-            let text = caps.get(4).map(|m| m.as_str()).unwrap_or("").to_string();
-            items.push(LineItem::Task(Task {
-                indent,
-                bullet,
-                completed: mark.eq_ignore_ascii_case("x"),
-                text,
-            }));
-        }
-    }
+    let items = parse_lines(&data, format);
 
     let mod_time = fs::metadata(path)
         .and_then(|meta| meta.modified())
@@ -55,10 +50,55 @@ pub fn load_lines(path: &Path) -> Result<(Vec<LineItem>, SystemTime), std::io::E
     Ok((items, mod_time))
 }
 
-pub fn save_lines(path: &Path, lines: &[LineItem]) -> Result<SystemTime, std::io::Error> {
+// A section's own placement in `lines` gives it full-list context, so unlike
+// `format::render_line` this renders a cookie with the real computed counts
+// rather than the placeholder form.
+fn render_saved_line(lines: &[LineItem], i: usize, format: &dyn Format) -> String {
+    match &lines[i] {
+        LineItem::Section {
+            title,
+            cookie: Some(kind),
+        } => {
+            let (done, total) = count_section_tasks(lines, i);
+            let cookie_text = match kind {
+                CookieKind::Fraction => format!("[{}/{}]", done, total),
+                CookieKind::Percent => {
+                    let pct = if total == 0 { 0 } else { done * 100 / total };
+                    format!("[{}%]", pct)
+                }
+            };
+            format.render_section(title, Some(&cookie_text))
+        }
+        other => render_line(other, format),
+    }
+}
+
+fn count_section_tasks(lines: &[LineItem], section_idx: usize) -> (usize, usize) {
+    let mut done = 0;
+    let mut total = 0;
+    for line in &lines[section_idx + 1..] {
+        match line {
+            LineItem::Section { .. } => break,
+            LineItem::Task(task) => {
+                total += 1;
+                if task.state.is_on() {
+                    done += 1;
+                }
+            }
+            LineItem::Raw { .. } | LineItem::Blank => {}
+        }
+    }
+    (done, total)
+}
+
+pub fn save_lines(
+    path: &Path,
+    lines: &[LineItem],
+    format: &dyn Format,
+) -> Result<SystemTime, std::io::Error> {
     let mut out = String::new();
-    for (i, line) in lines.iter().enumerate() {
-        out.push_str(&line.line());
+    for i in 0..lines.len() {
+        out.push_str(&render_saved_line(lines, i, format));
         if i < lines.len() - 1 {
             out.push('\n');
         }
@@ -71,3 +111,48 @@ pub fn save_lines(path: &Path, lines: &[LineItem]) -> Result<SystemTime, std::io
     let mod_time = fs::metadata(path)?.modified()?;
     Ok(mod_time)
 }
+
+pub enum SaveOutcome {
+    // Written without contention; nothing else touched the file in the
+    // meantime.
+    Saved(SystemTime),
+    // The file changed on disk since `expected_mtime`, so the write went
+    // through `merge::reconcile` first; `lines` is what actually got
+    // written and `conflicts` lists any clashes (a completion state, or a
+    // task renamed differently on both sides) the caller should surface.
+    Merged {
+        mod_time: SystemTime,
+        lines: Vec<LineItem>,
+        conflicts: Vec<String>,
+    },
+}
+
+// Like `save_lines`, but re-stats the file first: if it was modified since
+// `expected_mtime` (e.g. edited externally while the app was open), this
+// reconciles `lines` against the fresh on-disk version relative to
+// `base_lines` rather than clobbering whatever changed it.
+pub fn save_lines_checked(
+    path: &Path,
+    base_lines: &[LineItem],
+    lines: &[LineItem],
+    expected_mtime: SystemTime,
+    format: &dyn Format,
+) -> Result<SaveOutcome, std::io::Error> {
+    let on_disk_mtime = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    if on_disk_mtime <= expected_mtime {
+        let mod_time = save_lines(path, lines, format)?;
+        return Ok(SaveOutcome::Saved(mod_time));
+    }
+
+    let (theirs, _) = load_lines(path, format)?;
+    let reconciled = crate::merge::reconcile(base_lines, lines, &theirs);
+    let mod_time = save_lines(path, &reconciled.lines, format)?;
+    Ok(SaveOutcome::Merged {
+        mod_time,
+        lines: reconciled.lines,
+        conflicts: reconciled.conflicts,
+    })
+}
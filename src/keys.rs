@@ -10,6 +10,8 @@ pub enum Key {
     Down,
     Left,
     Right,
+    WordLeft,
+    WordRight,
     Ctrl(char),
     Backspace,
     Delete,
@@ -33,8 +35,26 @@ pub fn map_key(event: KeyEvent) -> Key {
         KeyCode::Esc => Key::Esc,
         KeyCode::Up => Key::Up,
         KeyCode::Down => Key::Down,
-        KeyCode::Left => Key::Left,
-        KeyCode::Right => Key::Right,
+        KeyCode::Left => {
+            if event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            {
+                Key::WordLeft
+            } else {
+                Key::Left
+            }
+        }
+        KeyCode::Right => {
+            if event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            {
+                Key::WordRight
+            } else {
+                Key::Right
+            }
+        }
         KeyCode::Backspace => Key::Backspace,
         KeyCode::Delete => Key::Delete,
         KeyCode::Tab => Key::Tab,
@@ -1,12 +1,20 @@
 mod app;
+mod checklist;
+mod clipboard;
+mod completion;
 mod edit;
 mod external_edit;
+mod format;
+mod fuzzy;
+mod increment;
 mod io;
 mod keys;
 mod markdown;
+mod merge;
 mod model;
 mod render;
 mod text_input;
+mod watch;
 
 use std::env;
 use std::fs;
@@ -17,8 +25,9 @@ use simplelog::{Config, WriteLogger};
 
 use crate::model::App;
 
-fn main() {
-    let (logging_on, path, explicit_path) = parse_args();
+#[tokio::main]
+async fn main() {
+    let (logging_on, mouse_on, path, explicit_path) = parse_args();
     if let Err(err) = init_logging(logging_on) {
         eprintln!("warning: failed to initialize logging: {}", err);
     }
@@ -39,22 +48,24 @@ fn main() {
         }
     };
 
-    if let Err(err) = app.run() {
+    if let Err(err) = app.run(mouse_on).await {
         eprintln!("error: {}", err);
         std::process::exit(1);
     }
 }
 
-fn parse_args() -> (bool, PathBuf, bool) {
+fn parse_args() -> (bool, bool, PathBuf, bool) {
     let mut logging_on = false;
+    let mut mouse_on = false;
     let mut path: Option<PathBuf> = None;
 
     for arg in env::args().skip(1) {
         match arg.as_str() {
             "--logs" | "-logs" => logging_on = true,
+            "--mouse" | "-mouse" => mouse_on = true,
             _ => {
                 if path.is_some() {
-                    eprintln!("usage: lazytodo [--logs] [path]");
+                    eprintln!("usage: lazytodo [--logs] [--mouse] [path]");
                     std::process::exit(1);
                 }
                 path = Some(PathBuf::from(arg));
@@ -64,7 +75,7 @@ fn parse_args() -> (bool, PathBuf, bool) {
 
     let explicit_path = path.is_some();
     let path = path.unwrap_or_else(|| PathBuf::from("todo.md"));
-    (logging_on, path, explicit_path)
+    (logging_on, mouse_on, path, explicit_path)
 }
 
 fn resolve_path(path: PathBuf, explicit_path: bool) -> Result<PathBuf, String> {
@@ -1,19 +1,32 @@
-use pulldown_cmark::{Event, Options, Parser, Tag};
+use once_cell::sync::Lazy;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
 use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Default)]
 struct Style {
     bold: bool,
     italic: bool,
     code: bool,
+    strikethrough: bool,
+    link: Option<String>,
 }
 
 #[derive(Debug, Clone)]
-struct Segment {
-    text: String,
-    style: Style,
+enum Segment {
+    // Regular inline text, wrapped and styled token-by-token.
+    Styled { text: String, style: Style },
+    // Pre-rendered, already line-broken text (syntax-highlighted code) that
+    // bypasses word wrapping so indentation/coloring survives intact.
+    Verbatim { text: String },
 }
 
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
 // Minimal inline markdown renderer that outputs ANSI-styled text and wraps to width.
 // It intentionally favors simplicity over completeness for parity with the Go UI.
 pub fn render_markdown_line(raw: &str, width: usize) -> String {
@@ -23,6 +36,8 @@ pub fn render_markdown_line(raw: &str, width: usize) -> String {
 
     let mut stack = vec![Style::default()];
     let mut segments: Vec<Segment> = Vec::new();
+    let mut fenced_lang: Option<String> = None;
+    let mut fenced_code = String::new();
 
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
@@ -30,40 +45,65 @@ pub fn render_markdown_line(raw: &str, width: usize) -> String {
     let parser = Parser::new_ext(raw, options);
     for event in parser {
         match event {
-            Event::Start(tag) => {
-                let mut next = *stack.last().unwrap_or(&Style::default());
-                match tag {
-                    Tag::Emphasis => next.italic = true,
-                    Tag::Strong => next.bold = true,
-                    Tag::CodeBlock(_) => next.code = true,
-                    _ => {}
+            Event::Start(tag) => match tag {
+                Tag::CodeBlock(CodeBlockKind::Fenced(lang)) if !lang.trim().is_empty() => {
+                    fenced_lang = Some(lang.to_string());
+                    fenced_code.clear();
+                }
+                other => {
+                    let mut next = stack.last().cloned().unwrap_or_default();
+                    match other {
+                        Tag::Emphasis => next.italic = true,
+                        Tag::Strong => next.bold = true,
+                        Tag::Strikethrough => next.strikethrough = true,
+                        Tag::CodeBlock(_) => next.code = true,
+                        Tag::Link(_, dest_url, _title) => next.link = Some(dest_url.to_string()),
+                        _ => {}
+                    }
+                    stack.push(next);
+                }
+            },
+            Event::End(tag) => {
+                if let Tag::CodeBlock(CodeBlockKind::Fenced(lang)) = &tag {
+                    if !lang.trim().is_empty() {
+                        if let Some(lang) = fenced_lang.take() {
+                            segments.push(Segment::Verbatim {
+                                text: highlight_code_block(&fenced_code, &lang),
+                            });
+                            fenced_code.clear();
+                            continue;
+                        }
+                    }
                 }
-                stack.push(next);
-            }
-            Event::End(_) => {
                 if stack.len() > 1 {
                     stack.pop();
                 }
             }
-            Event::Text(text) => segments.push(Segment {
-                text: text.to_string(),
-                style: *stack.last().unwrap_or(&Style::default()),
-            }),
+            Event::Text(text) => {
+                if fenced_lang.is_some() {
+                    fenced_code.push_str(&text);
+                } else {
+                    segments.push(Segment::Styled {
+                        text: text.to_string(),
+                        style: stack.last().cloned().unwrap_or_default(),
+                    });
+                }
+            }
             Event::Code(text) => {
-                let mut style = *stack.last().unwrap_or(&Style::default());
+                let mut style = stack.last().cloned().unwrap_or_default();
                 style.code = true;
-                segments.push(Segment {
+                segments.push(Segment::Styled {
                     text: text.to_string(),
                     style,
                 });
             }
-            Event::SoftBreak => segments.push(Segment {
+            Event::SoftBreak => segments.push(Segment::Styled {
                 text: " ".to_string(),
-                style: *stack.last().unwrap_or(&Style::default()),
+                style: stack.last().cloned().unwrap_or_default(),
             }),
-            Event::HardBreak => segments.push(Segment {
+            Event::HardBreak => segments.push(Segment::Styled {
                 text: "\n".to_string(),
-                style: *stack.last().unwrap_or(&Style::default()),
+                style: stack.last().cloned().unwrap_or_default(),
             }),
             _ => {}
         }
@@ -76,37 +116,48 @@ pub fn render_markdown_line(raw: &str, width: usize) -> String {
 }
 
 fn wrap_segments(segments: &[Segment], width: usize) -> String {
-    if width == 0 {
-        return segments_to_string(segments);
-    }
-
     let mut lines: Vec<String> = Vec::new();
     let mut current = String::new();
     let mut current_width = 0usize;
 
     for segment in segments {
-        for token in tokenize(&segment.text) {
-            if token == "\n" {
-                lines.push(current);
-                current = String::new();
-                current_width = 0;
-                continue;
+        match segment {
+            Segment::Verbatim { text } => {
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                    current_width = 0;
+                }
+                lines.extend(text.split('\n').map(str::to_string));
             }
+            Segment::Styled { text, style } => {
+                if width == 0 {
+                    current.push_str(&apply_style(text, style));
+                    continue;
+                }
+                for token in tokenize(text) {
+                    if token == "\n" {
+                        lines.push(std::mem::take(&mut current));
+                        current_width = 0;
+                        continue;
+                    }
 
-            let token_width = UnicodeWidthStr::width(token.as_str());
-            if !token.trim().is_empty() && current_width > 0 && current_width + token_width > width
-            {
-                lines.push(current);
-                current = String::new();
-                current_width = 0;
-            }
+                    let token_width = UnicodeWidthStr::width(token.as_str());
+                    if !token.trim().is_empty()
+                        && current_width > 0
+                        && current_width + token_width > width
+                    {
+                        lines.push(std::mem::take(&mut current));
+                        current_width = 0;
+                    }
 
-            if current_width == 0 && token == " " {
-                continue;
-            }
+                    if current_width == 0 && token == " " {
+                        continue;
+                    }
 
-            current.push_str(&apply_style(&token, segment.style));
-            current_width = current_width.saturating_add(token_width);
+                    current.push_str(&apply_style(&token, style));
+                    current_width = current_width.saturating_add(token_width);
+                }
+            }
         }
     }
 
@@ -145,15 +196,7 @@ fn tokenize(text: &str) -> Vec<String> {
     tokens
 }
 
-fn segments_to_string(segments: &[Segment]) -> String {
-    let mut out = String::new();
-    for seg in segments {
-        out.push_str(&apply_style(&seg.text, seg.style));
-    }
-    out
-}
-
-fn apply_style(text: &str, style: Style) -> String {
+fn apply_style(text: &str, style: &Style) -> String {
     let mut codes = Vec::new();
     if style.bold {
         codes.push("1");
@@ -164,10 +207,53 @@ fn apply_style(text: &str, style: Style) -> String {
     if style.code {
         codes.push("7");
     }
+    if style.strikethrough {
+        codes.push("9");
+    }
+    if style.link.is_some() {
+        codes.push("4");
+    }
+
+    let styled = if codes.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    };
+
+    match &style.link {
+        // OSC 8 hyperlink escape: supporting terminals make this clickable.
+        Some(url) => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, styled),
+        None => styled,
+    }
+}
+
+// Syntax-highlight a fenced code block's lines with syntect, falling back to
+// the plain reverse-video `code` style when the language isn't recognized.
+fn highlight_code_block(code: &str, lang: &str) -> String {
+    let Some(syntax) = SYNTAX_SET
+        .find_syntax_by_token(lang)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(lang))
+    else {
+        return fallback_code_block(code);
+    };
+    let theme = &THEME_SET.themes["base16-ocean.dark"];
+    let mut highlighter = HighlightLines::new(syntax, theme);
 
-    if codes.is_empty() {
-        return text.to_string();
+    let mut out = String::new();
+    for line in LinesWithEndings::from(code) {
+        let Ok(ranges) = highlighter.highlight_line(line, &SYNTAX_SET) else {
+            out.push_str(line);
+            continue;
+        };
+        out.push_str(&as_24_bit_terminal_escaped(&ranges[..], false));
+        out.push_str("\x1b[0m");
     }
+    out.trim_end_matches('\n').to_string()
+}
 
-    format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+fn fallback_code_block(code: &str) -> String {
+    code.lines()
+        .map(|line| format!("\x1b[7m{}\x1b[0m", line))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::model::{CheckState, LineItem, Task};
+
+// Result of reconciling our in-memory edits against a fresh on-disk version,
+// relative to `base` (the version both started from).
+pub struct Reconciled {
+    pub lines: Vec<LineItem>,
+    pub conflicts: Vec<String>,
+}
+
+// Three-way merge for the markdown todo file. `theirs` (freshly re-parsed
+// from disk) supplies the resulting structure, since it reflects whatever
+// external tool or editor touched the file; our local completion-state
+// changes are folded back in by matching tasks on their text, and any task
+// we added locally that never made it to disk is reinserted after the task
+// it followed locally (see `anchor_position`). A task
+// whose completion state changed on both sides to *different* values is a
+// true conflict: the disk's state wins, but it's reported so the caller can
+// tell the user. Likewise, a task renamed on both sides to *different* new
+// texts is a conflict: disk's text wins, but it's reported rather than
+// silently discarding the local rename.
+pub fn reconcile(base: &[LineItem], ours: &[LineItem], theirs: &[LineItem]) -> Reconciled {
+    let base_state = task_states(base);
+    let ours_state = task_states(ours);
+    let theirs_texts: HashSet<&str> = theirs
+        .iter()
+        .filter_map(|line| match line {
+            LineItem::Task(task) => Some(task.text.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    // A task text-matched solely against `base`/`theirs` loses its identity
+    // the moment it's edited locally: `theirs` still carries the task under
+    // its old (base) text (disk hasn't seen the rename), while `ours` only
+    // has it under the new text, so the lookups above would treat them as
+    // two unrelated tasks — re-emitting the stale one from `theirs` and then
+    // appending the renamed one back as "new", duplicating it. `renames`
+    // recovers the identity by diffing the base/ours task lists, so a local
+    // rename (old text deleted, new text inserted at the same slot) can be
+    // told apart from an unrelated delete-elsewhere-plus-insert-elsewhere.
+    let base_task_texts = task_texts(base);
+    let ours_task_texts = task_texts(ours);
+    let ours_tasks: Vec<&Task> = ours
+        .iter()
+        .filter_map(|line| match line {
+            LineItem::Task(task) => Some(task),
+            _ => None,
+        })
+        .collect();
+    let renames: HashMap<&str, &Task> =
+        pair_renames(&diff_texts(&base_task_texts, &ours_task_texts))
+            .into_iter()
+            .map(|(base_idx, ours_idx)| (base_task_texts[base_idx], ours_tasks[ours_idx]))
+            .collect();
+    let renamed_ours_texts: HashSet<&str> =
+        renames.values().map(|task| task.text.as_str()).collect();
+
+    // Symmetric to `renames`, but for disk's own renames: maps a task's
+    // position among `theirs`' tasks back to the base text it was renamed
+    // from. Needed so a task renamed on *both* sides can still be recognized
+    // as the same base task instead of `renames`' lookup (keyed by base text)
+    // missing because `theirs` no longer carries that base text at all.
+    let theirs_task_texts = task_texts(theirs);
+    let theirs_renames: HashMap<usize, &str> =
+        pair_renames(&diff_texts(&base_task_texts, &theirs_task_texts))
+            .into_iter()
+            .map(|(base_idx, theirs_idx)| (theirs_idx, base_task_texts[base_idx]))
+            .collect();
+
+    let mut lines = Vec::with_capacity(theirs.len());
+    let mut conflicts = Vec::new();
+    let mut theirs_task_idx = 0;
+
+    for line in theirs {
+        match line {
+            LineItem::Task(task) => {
+                let idx = theirs_task_idx;
+                theirs_task_idx += 1;
+                // The base text this task corresponds to: recovered via
+                // `theirs_renames` if disk renamed it, otherwise its own
+                // text (unchanged on disk, so it still matches `base`/`ours`
+                // under that same text).
+                let identity_text = theirs_renames.get(&idx).copied().unwrap_or(task.text.as_str());
+
+                if let Some(&ours_renamed) = renames.get(identity_text) {
+                    // Disk only actually renamed this task if `idx` shows up
+                    // in `theirs_renames`; otherwise `task.text` just *is*
+                    // `identity_text` and disk left it alone, so ours' rename
+                    // applies cleanly (the common, non-conflicting case this
+                    // branch originally handled).
+                    let theirs_renamed_too = theirs_renames.contains_key(&idx);
+                    if theirs_renamed_too && ours_renamed.text != task.text {
+                        // Both sides renamed the same base task to different
+                        // texts. Neither rename is "unchanged", so there's no
+                        // basis to silently prefer one - report it and keep
+                        // disk's text, consistent with a true completion-state
+                        // conflict also preferring `theirs`.
+                        conflicts.push(format!(
+                            "\"{}\" was renamed to \"{}\" locally and to \"{}\" on disk",
+                            identity_text, ours_renamed.text, task.text
+                        ));
+                    }
+                    let final_state = reconcile_state(
+                        &task.text,
+                        base_state.get(identity_text).copied(),
+                        Some(ours_renamed.state),
+                        task.state,
+                        &mut conflicts,
+                    );
+                    let prefer_ours_text = !theirs_renamed_too || ours_renamed.text == task.text;
+                    let mut merged_task =
+                        if prefer_ours_text { ours_renamed.clone() } else { task.clone() };
+                    merged_task.state = final_state;
+                    lines.push(LineItem::Task(merged_task));
+                    continue;
+                }
+                let final_state = reconcile_state(
+                    task.text.as_str(),
+                    base_state.get(identity_text).copied(),
+                    ours_state.get(identity_text).copied(),
+                    task.state,
+                    &mut conflicts,
+                );
+                let mut merged_task = task.clone();
+                merged_task.state = final_state;
+                lines.push(LineItem::Task(merged_task));
+            }
+            other => lines.push(other.clone()),
+        }
+    }
+
+    for (idx, line) in ours.iter().enumerate() {
+        if let LineItem::Task(task) = line {
+            if renamed_ours_texts.contains(task.text.as_str()) {
+                continue; // already placed above, under its renamed base slot
+            }
+            if base_state.contains_key(task.text.as_str())
+                || theirs_texts.contains(task.text.as_str())
+            {
+                continue;
+            }
+            let insert_at = anchor_position(&lines, ours, idx);
+            lines.insert(insert_at, line.clone());
+        }
+    }
+
+    Reconciled { lines, conflicts }
+}
+
+fn reconcile_state(
+    text: &str,
+    base_state: Option<CheckState>,
+    ours_state: Option<CheckState>,
+    theirs_state: CheckState,
+    conflicts: &mut Vec<String>,
+) -> CheckState {
+    let (Some(base_state), Some(ours_state)) = (base_state, ours_state) else {
+        // New on either side since `base`; nothing to reconcile against.
+        return theirs_state;
+    };
+    if ours_state == base_state {
+        return theirs_state; // unchanged locally: take whatever's on disk
+    }
+    if theirs_state == base_state {
+        return ours_state; // unchanged on disk: take our local edit
+    }
+    if ours_state == theirs_state {
+        return ours_state; // both sides made the same change
+    }
+    conflicts.push(format!(
+        "\"{}\": completion state changed both locally and on disk",
+        text
+    ));
+    theirs_state
+}
+
+fn task_states(items: &[LineItem]) -> HashMap<&str, CheckState> {
+    items
+        .iter()
+        .filter_map(|line| match line {
+            LineItem::Task(task) => Some((task.text.as_str(), task.state)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn task_texts(items: &[LineItem]) -> Vec<&str> {
+    items
+        .iter()
+        .filter_map(|line| match line {
+            LineItem::Task(task) => Some(task.text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+// One step of a minimal edit script turning `a` into `b` (indices into the
+// original slices), from a standard LCS-based diff.
+enum DiffOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+// Classic O(n*m) LCS diff: a full table is cheap at todo-list sizes and
+// keeps this readable without pulling in a diff crate.
+fn diff_texts(a: &[&str], b: &[&str]) -> Vec<DiffOp> {
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            ops.push(DiffOp::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Delete(i));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..n).map(DiffOp::Delete));
+    ops.extend((j..m).map(DiffOp::Insert));
+    ops
+}
+
+// Pairs up each run of consecutive deletes with the run of consecutive
+// inserts that immediately follows it, positionally (first-with-first),
+// treating them as one task renamed in place rather than one task removed
+// and an unrelated one added. Leftover deletes/inserts in an uneven run
+// stay as plain removals/additions. Returns `(base_idx, ours_idx)` pairs.
+fn pair_renames(ops: &[DiffOp]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    let mut i = 0;
+    while i < ops.len() {
+        let mut deletes = Vec::new();
+        while let Some(DiffOp::Delete(idx)) = ops.get(i) {
+            deletes.push(*idx);
+            i += 1;
+        }
+        let mut inserts = Vec::new();
+        while let Some(DiffOp::Insert(idx)) = ops.get(i) {
+            inserts.push(*idx);
+            i += 1;
+        }
+        let both_empty = deletes.is_empty() && inserts.is_empty();
+        pairs.extend(deletes.into_iter().zip(inserts));
+        if both_empty {
+            i += 1; // an Equal op between runs
+        }
+    }
+    pairs
+}
+
+// Where to reinsert a locally-added task (`ours[idx]`) into the merged list:
+// right after the nearest earlier task in `ours` that's still present in
+// `merged`, or at the end if there's no such anchor.
+fn anchor_position(merged: &[LineItem], ours: &[LineItem], idx: usize) -> usize {
+    for line in ours[..idx].iter().rev() {
+        if let LineItem::Task(task) = line {
+            let found = merged.iter().position(|candidate| {
+                matches!(candidate, LineItem::Task(t) if t.text == task.text)
+            });
+            if let Some(pos) = found {
+                return pos + 1;
+            }
+        }
+    }
+    merged.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(text: &str, state: CheckState) -> LineItem {
+        LineItem::Task(Task {
+            indent: String::new(),
+            bullet: "-".to_string(),
+            state,
+            text: text.to_string(),
+        })
+    }
+
+    fn task_texts_of(lines: &[LineItem]) -> Vec<&str> {
+        lines
+            .iter()
+            .filter_map(|line| match line {
+                LineItem::Task(task) => Some(task.text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn local_rename_does_not_duplicate_when_disk_is_unrelated() {
+        // Regression test: a task renamed locally while an unrelated task
+        // elsewhere changed on disk used to come out twice - once under its
+        // stale base text (re-emitted verbatim from `theirs`) and once
+        // under its new text (appended back as if new).
+        let base = vec![task("Buy milk", CheckState::Off), task("Other task", CheckState::Off)];
+        let ours = vec![
+            task("Buy milk and eggs", CheckState::Off),
+            task("Other task", CheckState::Off),
+        ];
+        let theirs = vec![
+            task("Buy milk", CheckState::Off),
+            task("Other task", CheckState::On), // changed on disk only
+        ];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert_eq!(
+            task_texts_of(&reconciled.lines),
+            vec!["Buy milk and eggs", "Other task"]
+        );
+        assert!(reconciled.conflicts.is_empty());
+    }
+
+    #[test]
+    fn local_rename_carries_its_own_completion_change() {
+        let base = vec![task("Buy milk", CheckState::Off)];
+        let ours = vec![task("Buy milk and eggs", CheckState::On)];
+        let theirs = vec![task("Buy milk", CheckState::Off)];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert_eq!(task_texts_of(&reconciled.lines), vec!["Buy milk and eggs"]);
+        let LineItem::Task(merged) = &reconciled.lines[0] else {
+            panic!("expected a task");
+        };
+        assert_eq!(merged.state, CheckState::On);
+    }
+
+    #[test]
+    fn conflicting_completion_change_on_unrenamed_task_is_reported() {
+        let base = vec![task("Buy milk", CheckState::Off)];
+        let ours = vec![task("Buy milk", CheckState::On)];
+        let theirs = vec![task("Buy milk", CheckState::Partial)];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert_eq!(reconciled.conflicts.len(), 1);
+        let LineItem::Task(merged) = &reconciled.lines[0] else {
+            panic!("expected a task");
+        };
+        // Disk wins on a true conflict.
+        assert_eq!(merged.state, CheckState::Partial);
+    }
+
+    #[test]
+    fn both_sides_renaming_the_same_task_differently_is_a_conflict() {
+        // Regression test: `renames` (keyed by base text) used to be looked
+        // up with `theirs`' *current* text, which only matches when theirs
+        // didn't also rename the task. When both sides renamed it, the
+        // lookup missed, theirs' text passed through unmodified, and the
+        // local rename was silently dropped with no conflict reported.
+        let base = vec![task("Buy milk", CheckState::Off)];
+        let ours = vec![task("Buy milk and eggs", CheckState::Off)];
+        let theirs = vec![task("Buy milk urgently", CheckState::Off)];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert_eq!(reconciled.conflicts.len(), 1);
+        assert_eq!(task_texts_of(&reconciled.lines), vec!["Buy milk urgently"]);
+    }
+
+    #[test]
+    fn both_sides_renaming_the_same_task_the_same_way_is_not_a_conflict() {
+        let base = vec![task("Buy milk", CheckState::Off)];
+        let ours = vec![task("Buy milk and eggs", CheckState::On)];
+        let theirs = vec![task("Buy milk and eggs", CheckState::Off)];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert!(reconciled.conflicts.is_empty());
+        assert_eq!(task_texts_of(&reconciled.lines), vec!["Buy milk and eggs"]);
+        let LineItem::Task(merged) = &reconciled.lines[0] else {
+            panic!("expected a task");
+        };
+        // Unchanged on disk (matches theirs' own rename), so the local
+        // completion-state edit still carries through.
+        assert_eq!(merged.state, CheckState::On);
+    }
+
+    #[test]
+    fn disk_only_rename_still_carries_its_own_completion_change() {
+        // The symmetric case of `local_rename_carries_its_own_completion_change`:
+        // previously, a disk-only rename's fallback path looked up
+        // base/ours state by theirs' *new* text, which never matched either
+        // map (both are keyed by the base text), so the local completion
+        // edit was lost.
+        let base = vec![task("Buy milk", CheckState::Off)];
+        let ours = vec![task("Buy milk", CheckState::On)];
+        let theirs = vec![task("Buy milk urgently", CheckState::Off)];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert!(reconciled.conflicts.is_empty());
+        assert_eq!(task_texts_of(&reconciled.lines), vec!["Buy milk urgently"]);
+        let LineItem::Task(merged) = &reconciled.lines[0] else {
+            panic!("expected a task");
+        };
+        assert_eq!(merged.state, CheckState::On);
+    }
+
+    #[test]
+    fn genuinely_new_local_task_is_appended_once() {
+        let base = vec![task("Buy milk", CheckState::Off)];
+        let ours = vec![task("Buy milk", CheckState::Off), task("New task", CheckState::Off)];
+        let theirs = vec![task("Buy milk", CheckState::Off)];
+
+        let reconciled = reconcile(&base, &ours, &theirs);
+
+        assert_eq!(task_texts_of(&reconciled.lines), vec!["Buy milk", "New task"]);
+    }
+}
@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use regex::Regex;
+
+use crate::format::Format;
 use crate::text_input::TextInput;
 
 // Represents the current UI mode.
@@ -8,6 +11,7 @@ use crate::text_input::TextInput;
 pub enum Mode {
     Normal,
     Edit,
+    Search,
 }
 
 // Indicates whether we're updating an existing line or inserting a new one.
@@ -25,35 +29,94 @@ pub enum EditTarget {
     Section,
 }
 
+// A vim-style operator (`d`/`y`) waiting for its motion. Set by pressing the
+// operator key in `Normal` mode; cleared once a motion completes it (`dd`,
+// `dj`, `dG`, `dgg`, ...) or a key that is no motion cancels it. Count digits
+// and the first `g` of `dgg` leave it pending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingOperator {
+    None,
+    Delete,
+    Yank,
+}
+
+// Borrows org-mode's tri-state checkbox model: `On`/`Off` are set directly,
+// `Partial` is never the result of a toggle; it is read from a `[-]` mark or
+// derived when a parent task has some-but-not-all of its children done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Off,
+    Partial,
+    On,
+}
+
+impl CheckState {
+    pub fn from_mark(mark: &str) -> Self {
+        match mark {
+            "x" | "X" => CheckState::On,
+            "-" => CheckState::Partial,
+            _ => CheckState::Off,
+        }
+    }
+
+    pub fn mark(self) -> &'static str {
+        match self {
+            CheckState::On => "x",
+            CheckState::Partial => "-",
+            CheckState::Off => " ",
+        }
+    }
+
+    pub fn is_on(self) -> bool {
+        matches!(self, CheckState::On)
+    }
+
+    // Direct toggles only ever land on `On`/`Off`; `Partial` is read-only,
+    // computed from children.
+    pub fn toggled(self) -> Self {
+        if self.is_on() {
+            CheckState::Off
+        } else {
+            CheckState::On
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Task {
     pub indent: String,
     pub bullet: String,
-    pub completed: bool,
+    pub state: CheckState,
     pub text: String,
 }
 
-impl Task {
-    pub fn line(&self) -> String {
-        let mark = if self.completed { "x" } else { " " };
-        format!("{}{} [{}] {}", self.indent, self.bullet, mark, self.text)
-    }
+// Which statistics cookie form (org-mode convention) a section header wants,
+// e.g. `[2/5]` or `[40%]`. Recomputed from the section's tasks on every save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookieKind {
+    Fraction,
+    Percent,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LineItem {
     Task(Task),
-    Section { title: String },
+    Section {
+        title: String,
+        cookie: Option<CookieKind>,
+    },
+    // A line that isn't a recognized section or task (prose, a code fence, a
+    // plain bullet, ...), kept verbatim so loading a mixed-content markdown
+    // file doesn't lose whatever surrounds the todos.
+    Raw {
+        text: String,
+    },
+    // A blank line, tracked separately from `Raw` so round-tripping through
+    // `save_lines` doesn't need to special-case empty text.
+    Blank,
 }
 
 impl LineItem {
-    pub fn line(&self) -> String {
-        match self {
-            LineItem::Section { title } => format!("## {}", title),
-            LineItem::Task(task) => task.line(),
-        }
-    }
-
     pub fn is_task(&self) -> bool {
         matches!(self, LineItem::Task(_))
     }
@@ -63,13 +126,84 @@ impl LineItem {
     }
 }
 
+// How a search query is interpreted. Cycled with Ctrl-T from search mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    // Case-insensitive unless the query itself contains an uppercase letter
+    // (ripgrep/vim convention).
+    SmartCase,
+    // The query is compiled as a `regex` pattern.
+    Regex,
+    // The query must match as an in-order subsequence (see `fuzzy.rs`).
+    Fuzzy,
+}
+
+impl SearchMode {
+    pub fn next(self) -> Self {
+        match self {
+            SearchMode::SmartCase => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Fuzzy,
+            SearchMode::Fuzzy => SearchMode::SmartCase,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchMode::SmartCase => "smart-case",
+            SearchMode::Regex => "regex",
+            SearchMode::Fuzzy => "fuzzy",
+        }
+    }
+}
+
+// Tracks the current search so `n`/`N` can step through occurrences instead
+// of only filtering the list. `matches` holds line indices in document order;
+// `current` indexes into it. `spans` holds, per entry in `matches`, the byte
+// range of the matched substring within that line's text, for future
+// substring-level highlighting.
+#[derive(Debug, Clone, Default)]
+pub struct SearchState {
+    pub query: String,
+    pub matches: Vec<usize>,
+    pub current: usize,
+    pub spans: Vec<(usize, usize)>,
+}
+
+impl SearchState {
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+}
+
+// One node in the undo tree: a full snapshot taken right after an edit was
+// applied, plus enough structure to make `undo`/`redo` tree walks instead of
+// stack pops. Undoing moves `current` to `parent`; redoing moves it to the
+// most recently created entry in `children`, so making a new edit after an
+// undo starts a new branch instead of discarding the one that was undone.
+// The root revision (index 0) has `parent: None` and is never removed.
 #[derive(Debug, Clone)]
-pub struct UndoState {
+pub struct Revision {
     pub lines: Vec<LineItem>,
     pub cursor: usize,
+    pub timestamp: SystemTime,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
 }
 
-pub const MAX_UNDO_HISTORY: usize = 10;
+// Inline completion popup state for `#tag`/`@mention`/`due:` tokens while
+// editing a task (see `App::update_completion`/`render_completion_menu`).
+// `token_start` is the byte offset in `text_input`'s value where the
+// partial token begins, so accepting a candidate knows what to replace.
+#[derive(Debug, Clone)]
+pub struct CompletionState {
+    pub token_start: usize,
+    pub candidates: Vec<String>,
+    pub selected: usize,
+}
 
 // Indentation levels (4 states: none, 4, 8, 12 spaces)
 pub const INDENT_LEVELS: [&str; 4] = ["", "    ", "        ", "            "];
@@ -77,16 +211,36 @@ pub const INDENT_LEVELS: [&str; 4] = ["", "    ", "        ", "            "];
 #[derive(Debug)]
 pub struct App {
     pub file_path: PathBuf,
+    // The on-disk syntax, picked from `file_path`'s extension at load time
+    // (see `format::format_for_path`).
+    pub format: Box<dyn Format>,
     pub lines: Vec<LineItem>,
+    // Snapshot of `lines` as last read from or written to disk successfully;
+    // the common ancestor for reconciling a concurrent external edit.
+    pub base_lines: Vec<LineItem>,
     pub cursor: usize,
     pub mode: Mode,
     pub text_input: TextInput,
+    pub search_input: TextInput,
+    pub search_state: SearchState,
+    // Cursor position when the current search began, so `Esc` can restore
+    // it and incremental preview has somewhere to search forward from.
+    pub search_origin: usize,
+    pub search_mode: SearchMode,
+    // Cached compile of the query in `Regex` mode. Left untouched (not
+    // cleared) on a compile error, so a mid-edit invalid pattern keeps
+    // matching against the last valid one instead of the match set going
+    // blank.
+    pub compiled_regex: Option<Regex>,
     pub input_placeholder: String,
     pub edit_intent: EditIntent,
     pub edit_target: EditTarget,
     pub edit_index: Option<usize>,
     pub insert_index: Option<usize>,
     pub edit_template: Task,
+    // The inline completion popup for the task currently being edited;
+    // `None` when the cursor isn't inside a completable token.
+    pub completion: Option<CompletionState>,
     pub status_message: String,
     pub error: Option<String>,
     pub last_modified: SystemTime,
@@ -97,9 +251,35 @@ pub struct App {
     pub window_height: u16,
     pub renderer_width: usize,
     pub external_edit_idx: Option<usize>,
-    pub undo_stack: Vec<UndoState>,
-    pub redo_stack: Vec<UndoState>,
-    pub pending_d: bool,
+    pub revisions: Vec<Revision>,
+    pub current_revision: usize,
+    // Set by `save_undo_state`, right before a tracked mutation; tells
+    // `save_and_set_status` to materialize a new revision from the
+    // (by-then-mutated) `lines` instead of just persisting a navigation
+    // like `undo`/`redo`/`earlier`/`later`.
+    pub pending_revision: bool,
+    pub pending_operator: PendingOperator,
+    // Set when `g` is pressed in `Normal` mode, waiting to see whether the
+    // next key is `-`/`+` (time-travel) or another `g` (go to top).
+    pub pending_g: bool,
+    // Mirrors `pending_g`, but for a `g` pressed while `pending_operator` is
+    // set (e.g. the first `g` of `dgg`): waits for a second `g` before
+    // completing the operator against line 0, instead of a lone `g` acting
+    // as "line 0" immediately.
+    pub pending_operator_g: bool,
+    pub register: Vec<LineItem>,
+    // Digits typed so far for the count of the next operator or Ctrl-A/Ctrl-X
+    // (e.g. the "3" in `3dd` or the "2" in `d2j`); plain `j`/`k` moves ignore
+    // it. Consumed by `take_count`.
+    pub count_prefix: String,
     pub scroll_offset: usize,
+    // Maps each rendered body row (0-indexed from `body_row_offset`) to the
+    // `lines` index it displays, for mouse click-to-select. Rebuilt by
+    // `render` every frame; `None` for rows with no corresponding line (an
+    // editor row and any completion popup rows beneath it).
+    pub row_to_line: Vec<Option<usize>>,
+    // How many terminal rows (header + empty-state message, if any) precede
+    // `row_to_line`'s first entry. Rebuilt by `render` every frame.
+    pub body_row_offset: usize,
     pub should_quit: bool,
 }
@@ -1,10 +1,11 @@
 use std::path::Path;
+use std::time::SystemTime;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::markdown::render_markdown_line;
-use crate::model::{App, EditIntent, EditTarget, LineItem, Mode, Task};
+use crate::model::{App, CheckState, CompletionState, EditIntent, EditTarget, LineItem, Mode, Task};
 
 const WRAP_MARGIN: usize = 6;
 
@@ -82,14 +83,18 @@ impl App {
             (start + available_items).min(total_items)
         };
 
+        self.body_row_offset = header_lines + empty_lines;
+        let mut row_to_line = Vec::new();
         for view_pos in start..end {
             if let Some(edit_pos) = editor_pos {
                 if view_pos == edit_pos {
-                    if self.edit_target == EditTarget::Section {
-                        out.push_str(&self.render_section_editor_line(view_pos));
+                    let rendered = if self.edit_target == EditTarget::Section {
+                        self.render_section_editor_line(view_pos)
                     } else {
-                        out.push_str(&self.render_editor_line(&self.edit_template, view_pos));
-                    }
+                        self.render_editor_line(&self.edit_template, view_pos)
+                    };
+                    row_to_line.extend(std::iter::repeat(None).take(count_lines(&rendered)));
+                    out.push_str(&rendered);
                     continue;
                 }
             }
@@ -108,11 +113,15 @@ impl App {
                 && self.edit_intent == EditIntent::Update
                 && self.edit_index == Some(idx)
             {
-                if self.edit_target == EditTarget::Section {
-                    out.push_str(&self.render_section_editor_line(idx));
+                let rendered = if self.edit_target == EditTarget::Section {
+                    self.render_section_editor_line(idx)
                 } else if let LineItem::Task(task) = &self.lines[idx] {
-                    out.push_str(&self.render_editor_line(task, idx));
-                }
+                    self.render_editor_line(task, idx)
+                } else {
+                    String::new()
+                };
+                row_to_line.extend(std::iter::repeat(None).take(count_lines(&rendered)));
+                out.push_str(&rendered);
                 continue;
             }
 
@@ -120,15 +129,16 @@ impl App {
                 && self.edit_intent == EditIntent::Insert
                 && self.edit_index == Some(idx);
 
-            match &self.lines[idx] {
-                LineItem::Section { title } => {
-                    out.push_str(&self.render_section_line(title, idx, suppress_cursor));
-                }
-                LineItem::Task(task) => {
-                    out.push_str(&self.render_task_line(task, idx, suppress_cursor));
-                }
-            }
+            let rendered = match &self.lines[idx] {
+                LineItem::Section { title, .. } => self.render_section_line(title, idx, suppress_cursor),
+                LineItem::Task(task) => self.render_task_line(task, idx, suppress_cursor),
+                LineItem::Raw { text } => self.render_raw_line(text, idx, suppress_cursor),
+                LineItem::Blank => self.render_raw_line("", idx, suppress_cursor),
+            };
+            row_to_line.extend(std::iter::repeat(Some(idx)).take(count_lines(&rendered)));
+            out.push_str(&rendered);
         }
+        self.row_to_line = row_to_line;
 
         out.push_str(&footer);
         pad_view_to_window(out, self.window_height)
@@ -136,11 +146,15 @@ impl App {
 
     fn render_task_line(&self, task: &Task, index: usize, suppress_cursor: bool) -> String {
         let mut body = render_markdown_line(&task.text, self.renderer_width);
+        body = highlight_semantic_tokens(&body, &find_semantic_tokens(&strip_ansi(&body)));
         if self.search_active() && self.mode != Mode::Edit {
-            body = highlight_matches(&body, self.search_query());
+            let plain = strip_ansi(&body);
+            if let Some((_, positions)) = self.match_line(&plain, self.search_query()) {
+                body = highlight_matches(&body, &positions);
+            }
         }
         let indent = task.indent.replace('\t', "    ");
-        let checkbox = checkbox_symbol(task.completed);
+        let checkbox = checkbox_symbol(task.state);
 
         let mut lines = body.split('\n').collect::<Vec<_>>();
         if lines.is_empty() {
@@ -166,14 +180,42 @@ impl App {
 
     fn render_editor_line(&self, task: &Task, index: usize) -> String {
         let indent = task.indent.replace('\t', "    ");
-        let prefix = format!("{}{} ", indent, checkbox_symbol(task.completed));
+        let prefix = format!("{}{} ", indent, checkbox_symbol(task.state));
         let content = format!(
             "{}{}",
             prefix,
             self.text_input
                 .view(&self.input_placeholder, self.editor_width())
         );
-        format_line(self, index, true, false, &content)
+        let mut rendered = format_line(self, index, true, false, &content);
+        if let Some(completion) = &self.completion {
+            rendered.push_str(&self.render_completion_menu(completion));
+        }
+        rendered
+    }
+
+    // Renders the inline #tag/@mention/due: completion popup directly
+    // beneath the editor line, one candidate per row, with the selected
+    // entry picked out via `HIGHLIGHT_ON`/`OFF`.
+    fn render_completion_menu(&self, completion: &CompletionState) -> String {
+        let mut out = String::new();
+        for (i, candidate) in completion.candidates.iter().enumerate() {
+            let line = format!("      {}", candidate);
+            if i == completion.selected {
+                out.push_str(HIGHLIGHT_ON);
+                out.push_str(&line);
+                out.push_str(CLEAR_TO_EOL);
+                out.push_str(HIGHLIGHT_OFF);
+            } else {
+                out.push_str(&line);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn render_raw_line(&self, text: &str, index: usize, suppress_cursor: bool) -> String {
+        format_line(self, index, false, suppress_cursor, text)
     }
 
     fn render_section_line(&self, title: &str, index: usize, suppress_cursor: bool) -> String {
@@ -195,7 +237,7 @@ impl App {
         for line in &self.lines {
             if let LineItem::Task(task) = line {
                 total_tasks += 1;
-                if task.completed {
+                if task.state.is_on() {
                     completed += 1;
                 }
             }
@@ -216,6 +258,9 @@ impl App {
                 "dd del",
                 "u undo",
                 "^r redo",
+                "g- older · g+ newer",
+                "^a/^x bump",
+                "yy/p/P yank-paste",
                 "/ search",
                 "e vim",
                 "i inline",
@@ -227,6 +272,8 @@ impl App {
                 parts.push("Esc cancel selection");
             }
             if self.search_active() && self.mode != Mode::Edit {
+                parts.push("n/N next/prev match");
+                parts.push("^t cycle match mode");
                 parts.push("Esc clear search");
             }
         }
@@ -245,11 +292,12 @@ impl App {
 
         let search_line = if self.mode == Mode::Search {
             format!(
-                "/{}",
+                "/[{}] {}",
+                self.search_mode.label(),
                 self.search_input.view("search", self.editor_width())
             )
         } else if self.search_active() && self.mode != Mode::Edit {
-            format!("/{}", self.search_query())
+            format!("/[{}] {}", self.search_mode.label(), self.search_query())
         } else {
             String::new()
         };
@@ -273,20 +321,35 @@ impl App {
         width.max(20)
     }
 
+    // Keep at least this many lines of context above/below the cursor when
+    // possible. Shrinks automatically (see below) once the viewport is too
+    // short to fit `2 * SCROLL_MARGIN + 1` rows.
+    const SCROLL_MARGIN: usize = 3;
+
     fn ensure_scroll(&mut self, total_items: usize, visible_items: usize, cursor_pos: usize) {
         if total_items == 0 {
             self.scroll_offset = 0;
             return;
         }
         let visible = visible_items.max(1);
+        // Degrade the margin gracefully in short viewports rather than
+        // letting it fight the hard edges and lock the cursor in place.
+        let margin = Self::SCROLL_MARGIN.min(visible.saturating_sub(1) / 2);
+
         if cursor_pos < self.scroll_offset {
             self.scroll_offset = cursor_pos;
         } else if cursor_pos >= self.scroll_offset + visible {
             self.scroll_offset = cursor_pos + 1 - visible;
         }
-        if self.scroll_offset > total_items.saturating_sub(1) {
-            self.scroll_offset = total_items.saturating_sub(1);
+
+        if cursor_pos < self.scroll_offset + margin {
+            self.scroll_offset = cursor_pos.saturating_sub(margin);
+        } else if cursor_pos + margin + 1 > self.scroll_offset + visible {
+            self.scroll_offset = cursor_pos + margin + 1 - visible;
         }
+
+        let max_offset = total_items.saturating_sub(visible.min(total_items));
+        self.scroll_offset = self.scroll_offset.min(max_offset);
     }
 }
 
@@ -298,11 +361,11 @@ pub fn render_header(path: &Path) -> String {
     format!("Managing {}\n\n", name)
 }
 
-fn checkbox_symbol(done: bool) -> &'static str {
-    if done {
-        "[x]"
-    } else {
-        "[ ]"
+fn checkbox_symbol(state: CheckState) -> &'static str {
+    match state {
+        CheckState::On => "[x]",
+        CheckState::Partial => "[-]",
+        CheckState::Off => "[ ]",
     }
 }
 
@@ -381,29 +444,21 @@ fn strip_ansi(input: &str) -> String {
     ANSI_ESCAPE_RE.replace_all(input, "").to_string()
 }
 
-fn highlight_matches(rendered: &str, query: &str) -> String {
-    if query.is_empty() || rendered.is_empty() {
-        return rendered.to_string();
-    }
-
-    let plain = strip_ansi(rendered);
-    if plain.is_empty() {
-        return rendered.to_string();
-    }
-
-    let ranges: Vec<(usize, usize)> = plain
-        .match_indices(query)
-        .map(|(start, _)| (start, start + query.len()))
-        .collect();
-    if ranges.is_empty() {
+// Wraps the bytes at `positions` (byte offsets into the ANSI-stripped plain
+// text of `rendered`) in `MATCH_ON`/`MATCH_OFF`, walking `rendered` itself so
+// existing escape codes (markdown styling, selection highlight) pass through
+// untouched. `positions` is precomputed by the caller via `App::match_line`,
+// so the highlight always reflects whichever search mode is active.
+fn highlight_matches(rendered: &str, positions: &[usize]) -> String {
+    if positions.is_empty() || rendered.is_empty() {
         return rendered.to_string();
     }
+    let matched: std::collections::HashSet<usize> = positions.iter().copied().collect();
 
     let bytes = rendered.as_bytes();
-    let mut out = String::with_capacity(rendered.len() + ranges.len() * 12);
+    let mut out = String::with_capacity(rendered.len() + matched.len() * 12);
     let mut i = 0usize;
     let mut plain_idx = 0usize;
-    let mut range_idx = 0usize;
     let mut active = false;
 
     while i < bytes.len() {
@@ -423,29 +478,211 @@ fn highlight_matches(rendered: &str, query: &str) -> String {
         }
 
         let ch = rendered[i..].chars().next().unwrap();
-        if range_idx < ranges.len() && plain_idx == ranges[range_idx].0 && !active {
+        let should_match = matched.contains(&plain_idx);
+        if should_match && !active {
             out.push_str(MATCH_ON);
             active = true;
+        } else if !should_match && active {
+            out.push_str(MATCH_OFF);
+            active = false;
         }
 
         out.push(ch);
         plain_idx += ch.len_utf8();
+        i += ch.len_utf8();
+    }
 
-        if active && range_idx < ranges.len() && plain_idx >= ranges[range_idx].1 {
-            out.push_str(MATCH_OFF);
-            active = false;
-            range_idx += 1;
-            if range_idx < ranges.len() && plain_idx == ranges[range_idx].0 {
-                out.push_str(MATCH_ON);
-                active = true;
+    if active {
+        out.push_str(MATCH_OFF);
+    }
+
+    out
+}
+
+// A semantic token kind recognized inside a task's rendered text, each with
+// its own foreground color so tags, mentions, dates, links, and priority
+// markers are distinguishable at a glance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Tag,
+    Mention,
+    Due,
+    DueOverdue,
+    Url,
+    Priority,
+}
+
+impl TokenKind {
+    fn on(self) -> &'static str {
+        match self {
+            TokenKind::Tag => "\x1b[38;5;111m",
+            TokenKind::Mention => "\x1b[38;5;213m",
+            TokenKind::Due => "\x1b[38;5;150m",
+            TokenKind::DueOverdue => "\x1b[1;38;5;203m",
+            TokenKind::Url => "\x1b[4;38;5;117m",
+            TokenKind::Priority => "\x1b[1;38;5;220m",
+        }
+    }
+
+    // Only turns off what `on` turned on (underline/bold plus foreground),
+    // so it doesn't clobber markdown styling or selection highlight active
+    // around it.
+    fn off(self) -> &'static str {
+        match self {
+            TokenKind::DueOverdue | TokenKind::Priority => "\x1b[22;39m",
+            TokenKind::Url => "\x1b[24;39m",
+            TokenKind::Tag | TokenKind::Mention | TokenKind::Due => "\x1b[39m",
+        }
+    }
+}
+
+struct SemanticToken {
+    start: usize,
+    end: usize,
+    kind: TokenKind,
+}
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"#[A-Za-z0-9_]+").expect("valid tag regex"));
+static MENTION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"@[A-Za-z0-9_]+").expect("valid mention regex"));
+static DUE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"due:(\d{4}-\d{2}-\d{2})").expect("valid due regex"));
+static URL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"https?://\S+").expect("valid url regex"));
+static PRIORITY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\([A-Z]\)|!+").expect("valid priority regex"));
+
+// Finds every semantic token in `plain` (`#tag`, `@mention`, `due:` dates,
+// bare URLs, priority markers), dropping any match that overlaps one found
+// earlier so e.g. a `due:` date's digits can't also be picked up as part of
+// another token.
+fn find_semantic_tokens(plain: &str) -> Vec<SemanticToken> {
+    let mut tokens = Vec::new();
+    for m in DUE_RE.find_iter(plain) {
+        let kind = if is_overdue(&m.as_str()[4..]) {
+            TokenKind::DueOverdue
+        } else {
+            TokenKind::Due
+        };
+        tokens.push(SemanticToken { start: m.start(), end: m.end(), kind });
+    }
+    for m in URL_RE.find_iter(plain) {
+        tokens.push(SemanticToken { start: m.start(), end: m.end(), kind: TokenKind::Url });
+    }
+    for m in TAG_RE.find_iter(plain) {
+        tokens.push(SemanticToken { start: m.start(), end: m.end(), kind: TokenKind::Tag });
+    }
+    for m in MENTION_RE.find_iter(plain) {
+        tokens.push(SemanticToken { start: m.start(), end: m.end(), kind: TokenKind::Mention });
+    }
+    for m in PRIORITY_RE.find_iter(plain) {
+        tokens.push(SemanticToken { start: m.start(), end: m.end(), kind: TokenKind::Priority });
+    }
+
+    tokens.sort_by_key(|t| t.start);
+    tokens.retain({
+        let mut last_end = 0usize;
+        move |t| {
+            if t.start < last_end {
+                return false;
             }
+            last_end = t.end;
+            true
         }
+    });
+    tokens
+}
+
+fn is_overdue(date: &str) -> bool {
+    today_string().map_or(false, |today| date < today.as_str())
+}
+
+fn today_string() -> Option<String> {
+    let days = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_secs()
+        / 86_400;
+    let (y, m, d) = civil_from_days(days as i64);
+    Some(format!("{:04}-{:02}-{:02}", y, m, d))
+}
+
+// Days-since-epoch to (year, month, day), Howard Hinnant's
+// `civil_from_days` (http://howardhinnant.github.io/date_algorithms.html).
+// Used instead of pulling in a date/time crate just to compare "today"
+// against a `due:` token.
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = if m <= 2 { y + 1 } else { y };
+    (y, m, d)
+}
+
+// Wraps semantic tokens in distinct ANSI color pairs, using the same
+// escape-aware byte walk as `highlight_matches` so positions are computed
+// against the ANSI-stripped plain text and existing markdown/selection
+// codes pass through untouched.
+fn highlight_semantic_tokens(rendered: &str, tokens: &[SemanticToken]) -> String {
+    if tokens.is_empty() || rendered.is_empty() {
+        return rendered.to_string();
+    }
+
+    let bytes = rendered.as_bytes();
+    let mut out = String::with_capacity(rendered.len() + tokens.len() * 12);
+    let mut i = 0usize;
+    let mut plain_idx = 0usize;
+    let mut token_idx = 0usize;
+    let mut active: Option<TokenKind> = None;
 
+    while i < bytes.len() {
+        if bytes[i] == 0x1b && i + 1 < bytes.len() && bytes[i + 1] == b'[' {
+            let mut j = i + 2;
+            while j < bytes.len() && bytes[j] != b'm' {
+                j += 1;
+            }
+            if j < bytes.len() {
+                out.push_str(&rendered[i..=j]);
+                i = j + 1;
+                if let Some(kind) = active {
+                    out.push_str(kind.on());
+                }
+                continue;
+            }
+        }
+
+        let ch = rendered[i..].chars().next().unwrap();
+
+        while token_idx < tokens.len() && plain_idx >= tokens[token_idx].end {
+            token_idx += 1;
+        }
+        let want = tokens
+            .get(token_idx)
+            .filter(|t| plain_idx >= t.start && plain_idx < t.end)
+            .map(|t| t.kind);
+
+        if want != active {
+            if let Some(kind) = active {
+                out.push_str(kind.off());
+            }
+            if let Some(kind) = want {
+                out.push_str(kind.on());
+            }
+            active = want;
+        }
+
+        out.push(ch);
+        plain_idx += ch.len_utf8();
         i += ch.len_utf8();
     }
 
-    if active {
-        out.push_str(MATCH_OFF);
+    if let Some(kind) = active {
+        out.push_str(kind.off());
     }
 
     out
@@ -19,6 +19,19 @@ impl TextInput {
         &self.value
     }
 
+    // Byte offset of the cursor into `value`.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    // Replaces `value[start..end]` with `text`, leaving the cursor right
+    // after the inserted text. Used to swap a partial token for a chosen
+    // completion candidate.
+    pub fn replace_range(&mut self, start: usize, end: usize, text: &str) {
+        self.value.replace_range(start..end, text);
+        self.cursor = start + text.len();
+    }
+
     pub fn set_value(&mut self, value: String) {
         self.value = value;
         self.cursor = self.value.len();
@@ -51,6 +64,34 @@ impl TextInput {
         self.cursor = self.value.len();
     }
 
+    pub fn move_word_left(&mut self) {
+        self.cursor = prev_word_boundary(&self.value, self.cursor);
+    }
+
+    pub fn move_word_right(&mut self) {
+        self.cursor = next_word_boundary(&self.value, self.cursor);
+    }
+
+    pub fn delete_word_backward(&mut self) {
+        let start = prev_word_boundary(&self.value, self.cursor);
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn delete_word_forward(&mut self) {
+        let end = next_word_boundary(&self.value, self.cursor);
+        self.value.replace_range(self.cursor..end, "");
+    }
+
+    pub fn kill_to_end(&mut self) {
+        self.value.truncate(self.cursor);
+    }
+
+    pub fn kill_to_start(&mut self) {
+        self.value.replace_range(0..self.cursor, "");
+        self.cursor = 0;
+    }
+
     pub fn insert_char(&mut self, ch: char) {
         self.value.insert(self.cursor, ch);
         self.cursor = next_char_boundary(&self.value, self.cursor);
@@ -113,6 +154,64 @@ impl TextInput {
     }
 }
 
+#[derive(PartialEq, Eq)]
+enum CharClass {
+    Alnum,
+    Punct,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_alphanumeric() || c == '_' {
+        CharClass::Alnum
+    } else {
+        CharClass::Punct
+    }
+}
+
+// Word motions over char boundaries: skip trailing whitespace, then consume
+// a run of the same character class (alphanumeric vs. punctuation).
+fn prev_word_boundary(s: &str, idx: usize) -> usize {
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let mut i = chars
+        .iter()
+        .position(|&(b, _)| b >= idx)
+        .unwrap_or(chars.len());
+    if i == 0 {
+        return 0;
+    }
+    i -= 1;
+    while i > 0 && chars[i].1.is_whitespace() {
+        i -= 1;
+    }
+    if chars[i].1.is_whitespace() {
+        return 0;
+    }
+    let class = char_class(chars[i].1);
+    while i > 0 && char_class(chars[i - 1].1) == class {
+        i -= 1;
+    }
+    chars[i].0
+}
+
+fn next_word_boundary(s: &str, idx: usize) -> usize {
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let mut i = chars
+        .iter()
+        .position(|&(b, _)| b >= idx)
+        .unwrap_or(chars.len());
+    while i < chars.len() && chars[i].1.is_whitespace() {
+        i += 1;
+    }
+    if i >= chars.len() {
+        return s.len();
+    }
+    let class = char_class(chars[i].1);
+    while i < chars.len() && char_class(chars[i].1) == class {
+        i += 1;
+    }
+    chars.get(i).map(|&(b, _)| b).unwrap_or(s.len())
+}
+
 fn prev_char_boundary(s: &str, idx: usize) -> usize {
     let mut prev = 0;
     for (i, _) in s.char_indices() {
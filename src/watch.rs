@@ -0,0 +1,50 @@
+use std::future;
+use std::path::Path;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+// Watches `path`'s parent directory, rather than the file itself, so the
+// watch survives editors that save via rename-into-place (write a temp
+// file, then rename over the original) instead of writing in place.
+pub struct FileWatcher {
+    // Kept alive only to keep the watcher (and its background thread)
+    // running; never read directly.
+    _watcher: RecommendedWatcher,
+    events: UnboundedReceiver<notify::Result<notify::Event>>,
+}
+
+impl FileWatcher {
+    pub fn new(path: &Path) -> notify::Result<Self> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        // `notify` calls back from its own OS-event thread, not an async
+        // context; an unbounded `tokio::sync::mpsc` sender's `send` is a
+        // plain non-blocking push, so the callback can forward straight
+        // into a channel `run`'s `select!` can `.await` directly, instead
+        // of needing a `std::sync::mpsc` receiver polled on a timer.
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        })?;
+        let dir = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Self { _watcher: watcher, events: rx })
+    }
+
+    // Awaits the next filesystem event, then drains whatever else already
+    // arrived, so a burst (e.g. an editor's write-then-rename save) collapses
+    // into a single reload. Errors from the watcher thread (e.g. the watched
+    // directory was removed) also count as "something happened" so the
+    // caller re-checks the file. If the watcher thread has gone away, this
+    // never resolves again rather than spinning the caller's `select!` in a
+    // tight loop on an always-closed channel.
+    pub async fn next_change(&mut self) -> bool {
+        if self.events.recv().await.is_none() {
+            return future::pending().await;
+        }
+        while self.events.try_recv().is_ok() {}
+        true
+    }
+}